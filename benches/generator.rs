@@ -0,0 +1,152 @@
+use std::fmt::Write;
+
+// shape of a generated journal. the same options always generate the same journal.
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub transactions: usize,
+    pub accounts: usize,
+    pub commodities: usize,
+    pub prices: usize,
+    pub periodic_rules: usize,
+    pub seed: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            transactions: 1_000,
+            accounts: 50,
+            commodities: 5,
+            prices: 100,
+            periodic_rules: 5,
+            seed: 1,
+        }
+    }
+}
+
+// xorshift, so that journals do not depend on a random number crate
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        usize::try_from(self.next() % n.max(1) as u64).unwrap_or_default()
+    }
+}
+
+const TOP_LEVEL: [&str; 5] = ["assets", "liabilities", "equity", "income", "expenses"];
+
+const PAYEES: [&str; 8] = [
+    "grocery store",
+    "coffee shop",
+    "landlord",
+    "employer",
+    "book store",
+    "gas station",
+    "pharmacy",
+    "restaurant",
+];
+
+fn account(index: usize) -> String {
+    format!(
+        "{}:group{}:account{index}",
+        TOP_LEVEL[index % TOP_LEVEL.len()],
+        index / TOP_LEVEL.len() % 7
+    )
+}
+
+// commodity names made of letters, AAA, AAB, ...
+fn commodity(index: usize) -> String {
+    let letter = |n: usize| char::from(b'A' + u8::try_from(n % 26).unwrap_or_default());
+    [index / 676, index / 26, index]
+        .into_iter()
+        .map(letter)
+        .collect()
+}
+
+// amount in one of the ways amounts are written, to exercise every branch of the amount parser
+fn amount(random: &mut Random, commodities: usize) -> String {
+    let cents = random.below(100_000) + 1;
+    let (units, cents) = (cents / 100, cents % 100);
+    let name = commodity(random.below(commodities));
+    match random.below(6) {
+        0 => format!("${units}.{cents:02}"),
+        1 => format!("$ {units}.{cents:02}"),
+        2 => format!("{units}.{cents:02} {name}"),
+        3 => format!("{name} {units}.{cents:02}"),
+        4 => format!("{units} {name} @ ${cents}.50"),
+        _ => format!("\"{name} 1\" {units}"),
+    }
+}
+
+#[must_use]
+pub fn journal(options: &Options) -> String {
+    let mut random = Random(options.seed.max(1));
+    let mut journal = String::new();
+    let accounts = options.accounts.max(2);
+
+    for index in 0..options.commodities {
+        writeln!(journal, "commodity {}1,000.00", commodity(index)).unwrap();
+    }
+    for index in 0..accounts {
+        writeln!(journal, "account {}", account(index)).unwrap();
+    }
+    writeln!(journal).unwrap();
+
+    for index in 0..options.prices {
+        let day = index % 28 + 1;
+        let month = index / 28 % 12 + 1;
+        writeln!(
+            journal,
+            "P 2020-{month:02}-{day:02} {} ${}.{:02}",
+            commodity(random.below(options.commodities)),
+            random.below(500) + 1,
+            random.below(100)
+        )
+        .unwrap();
+    }
+    writeln!(journal).unwrap();
+
+    for _ in 0..options.periodic_rules {
+        writeln!(journal, "~ monthly from 2020-01-01  budget").unwrap();
+        writeln!(
+            journal,
+            "    ({})  ${}",
+            account(random.below(accounts)),
+            random.below(1_000) + 1
+        )
+        .unwrap();
+        writeln!(journal).unwrap();
+    }
+
+    for index in 0..options.transactions {
+        let day = index % 28 + 1;
+        let month = index / 28 % 12 + 1;
+        let year = 2020 + index / 336;
+        let status = ["", " *", " !"][random.below(3)];
+        let payee = PAYEES[random.below(PAYEES.len())];
+        writeln!(
+            journal,
+            "{year}-{month:02}-{day:02}{status} {payee} | purchase {index}  ; note: {index}"
+        )
+        .unwrap();
+        for _ in 0..=random.below(3) {
+            writeln!(
+                journal,
+                "    {}  {}",
+                account(random.below(accounts)),
+                amount(&mut random, options.commodities.max(1))
+            )
+            .unwrap();
+        }
+        writeln!(journal, "    {}", account(random.below(accounts))).unwrap();
+        writeln!(journal).unwrap();
+    }
+    journal
+}
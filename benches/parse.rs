@@ -0,0 +1,56 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod generator;
+
+use generator::{journal, Options};
+
+const CHEATSHEET_JOURNAL: &str = include_str!("../examples/fixture/cheatsheet.journal");
+
+fn cheatsheet(c: &mut Criterion) {
+    c.bench_function("cheatsheet", |b| {
+        b.iter(|| hledger_parser::parse(CHEATSHEET_JOURNAL).unwrap());
+    });
+}
+
+fn generated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated");
+    for transactions in [100, 1_000, 10_000] {
+        let journal = journal(&Options {
+            transactions,
+            ..Options::default()
+        });
+        group.throughput(Throughput::Bytes(journal.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("parse", transactions),
+            &journal,
+            |b, journal| {
+                b.iter(|| hledger_parser::parse(journal).unwrap());
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_borrowed", transactions),
+            &journal,
+            |b, journal| b.iter(|| hledger_parser::borrowed::parse(journal).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_parallel", transactions),
+            &journal,
+            |b, journal| b.iter(|| hledger_parser::parse_parallel(journal).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("parse_reader", transactions),
+            &journal,
+            |b, journal| {
+                b.iter(|| {
+                    hledger_parser::parse_reader(journal.as_bytes())
+                        .collect::<Result<Vec<_>, _>>()
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, cheatsheet, generated);
+criterion_main!(benches);
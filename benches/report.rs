@@ -0,0 +1,40 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use hledger_parser::journal::Journal;
+use hledger_parser::report::balance::{balance, Options as BalanceOptions};
+
+mod generator;
+
+use generator::{journal, Options};
+
+// balances of every account from the directives, and from a journal with interned accounts and
+// commodities, which is built once and queried again after that
+fn balances(c: &mut Criterion) {
+    let mut group = c.benchmark_group("balances");
+    for transactions in [1_000, 10_000] {
+        let directives = hledger_parser::parse(&journal(&Options {
+            transactions,
+            ..Options::default()
+        }))
+        .unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("balance_report", transactions),
+            &directives,
+            |b, directives| b.iter(|| balance(directives, &BalanceOptions::default()).unwrap()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("journal_new", transactions),
+            &directives,
+            |b, directives| b.iter(|| Journal::new(directives.clone()).unwrap()),
+        );
+        let journal = Journal::new(directives).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("journal_balances", transactions),
+            &journal,
+            |b, journal| b.iter(|| journal.balances().unwrap()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, balances);
+criterion_main!(benches);
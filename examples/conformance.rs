@@ -0,0 +1,202 @@
+// runs shelltest files, in the format of hledger's own tests, through the parser and reports
+// how many of them it agrees with. tests that expect hledger to succeed should parse, tests that
+// expect it to fail should not. only tests reading the journal from stdin are run, the others and
+// files in no shelltest format are reported as skipped.
+//
+// cargo run --example conformance [directory with .test files]
+
+use std::path::{Path, PathBuf};
+
+struct Test {
+    command: String,
+    input: String,
+    exit_code: i32,
+}
+
+#[derive(Clone, Copy)]
+enum Block {
+    Input,
+    Output,
+    Other,
+}
+
+// markers of a shelltest format. in format 1 a test starts with its command on a line of its own,
+// followed by its input; in formats 2 and 3 input comes first and is shared by the tests after it.
+struct Format {
+    input: &'static str,
+    command: Option<&'static str>,
+    output: &'static str,
+    exit_code: &'static str,
+}
+
+const FORMAT_1: Format = Format {
+    input: "<<<",
+    command: None,
+    output: ">>>",
+    exit_code: ">>>=",
+};
+
+const FORMAT_2: Format = Format {
+    input: "<<<",
+    command: Some("$$$ "),
+    output: ">>>",
+    exit_code: ">>>=",
+};
+
+const FORMAT_3: Format = Format {
+    input: "<",
+    command: Some("$ "),
+    output: ">",
+    exit_code: ">=",
+};
+
+fn format(contents: &str) -> Option<Format> {
+    if contents.lines().any(|line| line.starts_with("$$$ ")) {
+        Some(FORMAT_2)
+    } else if contents.lines().any(|line| line.starts_with("$ ")) {
+        Some(FORMAT_3)
+    } else if contents.lines().any(|line| line.starts_with(">>>")) {
+        Some(FORMAT_1)
+    } else {
+        None
+    }
+}
+
+// tests of a file in shelltest format 1, 2 or 3, or None if it is in none of them
+fn tests(contents: &str) -> Option<Vec<Test>> {
+    let format = format(contents)?;
+    let mut tests = Vec::<Test>::new();
+    let mut input = String::new();
+    let mut block = Block::Other;
+    for line in contents.lines() {
+        if line == format.input {
+            input.clear();
+            block = Block::Input;
+        } else if let Some(command) = format.command.and_then(|prefix| line.strip_prefix(prefix)) {
+            tests.push(Test {
+                command: command.to_string(),
+                input: input.clone(),
+                exit_code: 0,
+            });
+            block = Block::Other;
+        } else if let Some(exit_code) = line.strip_prefix(format.exit_code) {
+            if let Some(test) = tests.last_mut() {
+                // "!0" and regular expressions expect some failure
+                test.exit_code = exit_code.trim().parse().unwrap_or(1);
+            }
+            block = Block::Other;
+        } else if line.starts_with(format.output) {
+            block = Block::Output;
+        } else if let Block::Input = block {
+            let input = match (format.command, tests.last_mut()) {
+                (None, Some(test)) => &mut test.input,
+                _ => &mut input,
+            };
+            input.push_str(line);
+            input.push('\n');
+        } else if let (None, Block::Other) = (format.command, block) {
+            if !line.trim().is_empty() && !line.starts_with('#') {
+                tests.push(Test {
+                    command: line.to_string(),
+                    input: String::new(),
+                    exit_code: 0,
+                });
+            }
+        }
+    }
+    Some(tests)
+}
+
+fn reads_stdin(command: &str) -> bool {
+    let words = command.split_whitespace().collect::<Vec<_>>();
+    words.contains(&"-f-") || words.windows(2).any(|pair| pair == ["-f", "-"])
+}
+
+fn test_files(directory: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == "test")
+    });
+    files.sort();
+    Ok(files)
+}
+
+pub fn main() {
+    let directory = std::env::args().nth(1).map_or_else(
+        || Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/fixture/conformance"),
+        PathBuf::from,
+    );
+    let files = match test_files(&directory) {
+        Ok(files) => files,
+        Err(error) => {
+            eprintln!("{}: {error}", directory.display());
+            std::process::exit(1);
+        }
+    };
+
+    let (mut passed, mut total, mut skipped, mut skipped_files) = (0, 0, 0, 0);
+    for path in files {
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                eprintln!("{}: {error}", path.display());
+                continue;
+            }
+        };
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let Some(tests) = tests(&contents) else {
+            println!("{name}: skipped, not in shelltest format 1, 2 or 3");
+            skipped_files += 1;
+            continue;
+        };
+        let mut failures = Vec::new();
+        let (mut file_total, mut file_skipped) = (0, 0);
+        for (number, test) in tests.iter().enumerate() {
+            if !reads_stdin(&test.command) {
+                file_skipped += 1;
+                continue;
+            }
+            file_total += 1;
+            let result = hledger_parser::parse(&test.input);
+            match (&result, test.exit_code) {
+                (Ok(_), 0) | (Err(_), 1..) => {}
+                (Ok(_), _) => failures.push(format!("{}: parsed, expected an error", number + 1)),
+                (Err(errors), _) => {
+                    let error = errors.first().map_or_else(String::new, |error| {
+                        let line = test.input[..error.span().start].matches('\n').count() + 1;
+                        format!("line {line}: {error}")
+                    });
+                    failures.push(format!("{}: {error}", number + 1));
+                }
+            }
+        }
+        let file_passed = file_total - failures.len();
+        if file_skipped == 0 {
+            println!("{name}: {file_passed}/{file_total}");
+        } else {
+            println!("{name}: {file_passed}/{file_total}, {file_skipped} skipped");
+        }
+        for failure in failures {
+            println!("    {failure}");
+        }
+        passed += file_passed;
+        total += file_total;
+        skipped += file_skipped;
+    }
+
+    let percentage = if total == 0 {
+        0.0
+    } else {
+        f64::from(u32::try_from(passed).unwrap_or(u32::MAX)) * 100.0
+            / f64::from(u32::try_from(total).unwrap_or(u32::MAX))
+    };
+    println!("passed {passed} of {total} tests ({percentage:.1}%)");
+    if skipped > 0 || skipped_files > 0 {
+        println!(
+            "skipped {skipped} tests not reading the journal from stdin and {skipped_files} files"
+        );
+    }
+}
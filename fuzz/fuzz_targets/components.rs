@@ -0,0 +1,11 @@
+#![no_main]
+
+use hledger_parser::fuzz::COMPONENTS;
+use libfuzzer_sys::fuzz_target;
+
+// the first byte picks the component parser to run on the rest of the input
+fuzz_target!(|input: (u8, &str)| {
+    let (index, contents) = input;
+    let (_, run) = COMPONENTS[usize::from(index) % COMPONENTS.len()];
+    run(contents);
+});
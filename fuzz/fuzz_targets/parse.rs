@@ -0,0 +1,7 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|contents: &str| {
+    let _ = hledger_parser::parse(contents);
+});
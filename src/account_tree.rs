@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use crate::component::account_name::AccountName;
+use crate::directive::account::AccountType;
+use crate::directive::Directive;
+use crate::report::statement::account_type;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub account_name: AccountName,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    // position among the journal's account directives, if the account is declared
+    pub declaration: Option<usize>,
+    // declared type of the account or its nearest parent, otherwise guessed from its name
+    pub account_type: Option<AccountType>,
+    // postings to the account itself, not including its subaccounts
+    pub postings: usize,
+}
+
+impl Node {
+    // last part of the account name, empty for the root
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.account_name.0.last().map_or("", String::as_str)
+    }
+
+    // number of parts in the account name, zero for the root
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.account_name.0.len()
+    }
+}
+
+// declared and used accounts with all their parents, under a root node with an empty name.
+// children are ordered by declaration, with undeclared accounts after the declared ones in
+// alphabetical order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountTree {
+    nodes: Vec<Node>,
+    indices: HashMap<AccountName, usize>,
+}
+
+impl AccountTree {
+    #[must_use]
+    pub fn from_directives(directives: &[Directive]) -> Self {
+        let mut declared = HashMap::new();
+        let mut accounts = Vec::new();
+        for directive in directives {
+            match directive {
+                Directive::Account(account) => {
+                    if let Some(account_type) = account.account_type {
+                        declared.insert(account.account_name.clone(), account_type);
+                    }
+                    accounts.push((&account.account_name, true));
+                }
+                Directive::Transaction(transaction) => accounts.extend(
+                    transaction
+                        .postings
+                        .iter()
+                        .map(|posting| (&posting.account_name, false)),
+                ),
+                Directive::PeriodicTransaction(periodic) => accounts.extend(
+                    periodic
+                        .postings
+                        .iter()
+                        .map(|posting| (&posting.account_name, false)),
+                ),
+                _ => {}
+            }
+        }
+
+        let mut tree = Self {
+            nodes: vec![Node {
+                account_name: AccountName(Vec::new()),
+                parent: None,
+                children: Vec::new(),
+                declaration: None,
+                account_type: None,
+                postings: 0,
+            }],
+            indices: HashMap::new(),
+        };
+        let mut declarations = 0;
+        for (account_name, is_declaration) in accounts {
+            let index = tree.insert(account_name, &declared);
+            let node = &mut tree.nodes[index];
+            if !is_declaration {
+                node.postings += 1;
+            } else if node.declaration.is_none() {
+                node.declaration = Some(declarations);
+                declarations += 1;
+            }
+        }
+        tree.sort(0);
+        tree
+    }
+
+    fn insert(
+        &mut self,
+        account_name: &AccountName,
+        declared: &HashMap<AccountName, AccountType>,
+    ) -> usize {
+        let mut parent = 0;
+        for depth in 1..=account_name.0.len() {
+            let name = AccountName(account_name.0[..depth].to_vec());
+            parent = if let Some(index) = self.indices.get(&name) {
+                *index
+            } else {
+                let index = self.nodes.len();
+                self.nodes.push(Node {
+                    account_type: account_type(&name, declared),
+                    account_name: name.clone(),
+                    parent: Some(parent),
+                    children: Vec::new(),
+                    declaration: None,
+                    postings: 0,
+                });
+                self.nodes[parent].children.push(index);
+                self.indices.insert(name, index);
+                index
+            };
+        }
+        parent
+    }
+
+    fn sort(&mut self, index: usize) {
+        let mut children = std::mem::take(&mut self.nodes[index].children);
+        children.sort_by(|a, b| {
+            let (a, b) = (&self.nodes[*a], &self.nodes[*b]);
+            match (a.declaration, b.declaration) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.name().cmp(b.name()),
+            }
+        });
+        for child in &children {
+            self.sort(*child);
+        }
+        self.nodes[index].children = children;
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Node {
+        &self.nodes[0]
+    }
+
+    #[must_use]
+    pub fn get(&self, account_name: &AccountName) -> Option<&Node> {
+        self.indices
+            .get(account_name)
+            .map(|index| &self.nodes[*index])
+    }
+
+    #[must_use]
+    pub fn parent(&self, node: &Node) -> Option<&Node> {
+        node.parent.map(|index| &self.nodes[index])
+    }
+
+    pub fn children<'a>(&'a self, node: &'a Node) -> impl Iterator<Item = &'a Node> {
+        node.children.iter().map(|index| &self.nodes[*index])
+    }
+
+    // all nodes below the given one, depth first in tree order
+    #[must_use]
+    pub fn descendants<'a>(&'a self, node: &'a Node) -> Vec<&'a Node> {
+        let mut descendants = Vec::new();
+        let mut stack = node.children.iter().rev().collect::<Vec<_>>();
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[*index];
+            descendants.push(node);
+            stack.extend(node.children.iter().rev());
+        }
+        descendants
+    }
+
+    // accounts in tree order, without the root
+    #[must_use]
+    pub fn accounts(&self) -> Vec<&Node> {
+        self.descendants(self.root())
+    }
+
+    // accounts whose full name starts with the prefix, like "expenses:fo" for completions
+    #[must_use]
+    pub fn starting_with(&self, prefix: &str) -> Vec<&Node> {
+        self.accounts()
+            .into_iter()
+            .filter(|node| node.account_name.0.join(":").starts_with(prefix))
+            .collect()
+    }
+
+    // tree without the accounts deeper than the depth. postings of the removed accounts are
+    // counted in their ancestor at that depth.
+    #[must_use]
+    pub fn truncated(&self, depth: usize) -> Self {
+        let mut tree = self.clone();
+        for index in (1..tree.nodes.len()).rev() {
+            if tree.nodes[index].depth() <= depth {
+                continue;
+            }
+            let ancestor = AccountName(tree.nodes[index].account_name.0[..depth].to_vec());
+            let postings = tree.nodes[index].postings;
+            let ancestor = tree.indices.get(&ancestor).copied().unwrap_or(0);
+            tree.nodes[ancestor].postings += postings;
+        }
+        tree.nodes.retain(|node| node.depth() <= depth);
+        tree.indices = tree
+            .nodes
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(index, node)| (node.account_name.clone(), index))
+            .collect();
+        // indices of kept nodes shift, so links are rebuilt from the previous order
+        let order = self.accounts();
+        for node in &mut tree.nodes {
+            node.children.clear();
+        }
+        for node in order.into_iter().filter(|node| node.depth() <= depth) {
+            let index = tree.indices[&node.account_name];
+            let parent = match node.depth() {
+                1 => 0,
+                _ => tree.indices[&AccountName(node.account_name.0[..node.depth() - 1].to_vec())],
+            };
+            tree.nodes[index].parent = Some(parent);
+            tree.nodes[parent].children.push(index);
+        }
+        tree
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> AccountTree {
+        AccountTree::from_directives(
+            &crate::parse(
+                "account expenses
+account assets:bank  ; type: C
+account assets
+2024-01-01
+    expenses:food:dining  $10
+    assets:bank
+2024-01-02
+    expenses:food:groceries  $5
+    expenses:food:groceries  $5
+    assets:cash
+",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn names(nodes: &[&Node]) -> Vec<String> {
+        nodes
+            .iter()
+            .map(|node| node.account_name.0.join(":"))
+            .collect()
+    }
+
+    fn account_name(name: &str) -> AccountName {
+        AccountName::from_strs(&name.split(':').map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn declaration_order() {
+        let tree = tree();
+        assert_eq!(
+            names(&tree.accounts()),
+            vec![
+                "expenses",
+                "expenses:food",
+                "expenses:food:dining",
+                "expenses:food:groceries",
+                "assets",
+                "assets:bank",
+                "assets:cash",
+            ]
+        );
+    }
+
+    #[test]
+    fn navigation() {
+        let tree = tree();
+        let food = tree.get(&account_name("expenses:food")).unwrap();
+        assert_eq!(food.name(), "food");
+        assert_eq!(food.depth(), 2);
+        assert_eq!(tree.parent(food).map(Node::name), Some("expenses"));
+        assert_eq!(
+            names(&tree.children(food).collect::<Vec<_>>()),
+            vec!["expenses:food:dining", "expenses:food:groceries"]
+        );
+        assert_eq!(tree.parent(tree.root()), None);
+
+        let bank = tree.get(&account_name("assets:bank")).unwrap();
+        assert_eq!(bank.declaration, Some(1));
+        assert_eq!(bank.account_type, Some(AccountType::Cash));
+        assert_eq!(
+            tree.get(&account_name("assets:cash")).unwrap().account_type,
+            Some(AccountType::Asset)
+        );
+        assert_eq!(
+            tree.get(&account_name("expenses:food:groceries"))
+                .unwrap()
+                .postings,
+            2
+        );
+    }
+
+    #[test]
+    fn prefix_and_depth() {
+        let tree = tree();
+        assert_eq!(
+            names(&tree.starting_with("expenses:food:")),
+            vec!["expenses:food:dining", "expenses:food:groceries"]
+        );
+        assert_eq!(names(&tree.starting_with("as")).len(), 3);
+
+        let truncated = tree.truncated(2);
+        assert_eq!(
+            names(&truncated.accounts()),
+            vec![
+                "expenses",
+                "expenses:food",
+                "assets",
+                "assets:bank",
+                "assets:cash"
+            ]
+        );
+        assert_eq!(
+            truncated
+                .get(&account_name("expenses:food"))
+                .unwrap()
+                .postings,
+            3
+        );
+        assert_eq!(truncated.get(&account_name("expenses:food:dining")), None);
+    }
+}
@@ -0,0 +1,523 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use chumsky::error::Rich;
+use chumsky::span::SimpleSpan;
+use hledger_parser::account_tree::AccountTree;
+use hledger_parser::report::balance::{balance, Mode, Options};
+use hledger_parser::{AccountName, Directive};
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind,
+    OneOf, Position, PublishDiagnosticsParams, Range, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Sync + Send>>;
+
+#[derive(Default)]
+struct Document {
+    text: String,
+    // directives of the latest text that parsed, kept while the journal is being edited. their
+    // spans are moved along with the edits, so that they are in the current text.
+    directives: Vec<(Directive, SimpleSpan)>,
+    // errors of the latest text
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn main() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![String::from(":")]),
+            ..CompletionOptions::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    })?;
+    if let Err(error) = connection.initialize(capabilities) {
+        if error.channel_is_disconnected() {
+            io_threads.join()?;
+        }
+        return Err(error.into());
+    }
+
+    let mut documents = HashMap::<Url, Document>::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = handle_request(&documents, request)?;
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = handle_notification(&mut documents, notification)? {
+                    let diagnostics = documents
+                        .get(&uri)
+                        .map(|document| document.diagnostics.clone())
+                        .unwrap_or_default();
+                    let params = PublishDiagnosticsParams {
+                        uri,
+                        diagnostics,
+                        version: None,
+                    };
+                    connection
+                        .sender
+                        .send(Message::Notification(Notification::new(
+                            PublishDiagnostics::METHOD.to_string(),
+                            params,
+                        )))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    io_threads.join()?;
+    Ok(())
+}
+
+// updates the documents, returning the uri of a changed document
+fn handle_notification(
+    documents: &mut HashMap<Url, Document>,
+    notification: Notification,
+) -> Result<Option<Url>> {
+    let (uri, text) = match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params = serde_json::from_value::<
+                <DidOpenTextDocument as lsp_types::notification::Notification>::Params,
+            >(notification.params)?;
+            (params.text_document.uri, Some(params.text_document.text))
+        }
+        DidChangeTextDocument::METHOD => {
+            let params = serde_json::from_value::<
+                <DidChangeTextDocument as lsp_types::notification::Notification>::Params,
+            >(notification.params)?;
+            // full sync, so the last change holds the whole text
+            let text = params
+                .content_changes
+                .into_iter()
+                .last()
+                .map(|change| change.text);
+            (params.text_document.uri, text)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params = serde_json::from_value::<
+                <DidCloseTextDocument as lsp_types::notification::Notification>::Params,
+            >(notification.params)?;
+            documents.remove(&params.text_document.uri);
+            return Ok(Some(params.text_document.uri));
+        }
+        _ => return Ok(None),
+    };
+    let Some(text) = text else {
+        return Ok(None);
+    };
+    let document = documents.entry(uri.clone()).or_default();
+    match hledger_parser::parse_spanned(&text) {
+        Ok(directives) => {
+            document.directives = directives;
+            document.diagnostics = Vec::new();
+        }
+        Err(errors) => {
+            document.diagnostics = diagnostics(&text, &errors);
+            shift(&mut document.directives, &document.text, &text);
+        }
+    }
+    document.text = text;
+    Ok(Some(uri))
+}
+
+// moves the spans from the old text to the new one. the change is what is left of the text
+// between their common start and end. spans that overlap it are stretched or shrunk with it.
+fn shift(directives: &mut [(Directive, SimpleSpan)], old: &str, new: &str) {
+    let prefix = old
+        .char_indices()
+        .zip(new.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or(old.len().min(new.len()), |((index, _), _)| index);
+    let suffix = old[prefix..]
+        .chars()
+        .rev()
+        .zip(new[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum::<usize>();
+    let (removed_end, inserted_end) = (old.len() - suffix, new.len() - suffix);
+    let after = |offset: usize| offset.max(removed_end) - removed_end + inserted_end;
+    for (_, span) in directives {
+        // text inserted where a span starts or ends is outside of it
+        let start = if span.start >= removed_end {
+            after(span.start)
+        } else {
+            span.start.min(prefix)
+        };
+        let end = if span.end <= prefix {
+            span.end
+        } else {
+            after(span.end)
+        };
+        *span = SimpleSpan::new(start, end);
+    }
+}
+
+fn handle_request(documents: &HashMap<Url, Document>, request: Request) -> Result<Response> {
+    let id = request.id.clone();
+    let result = match request.method.as_str() {
+        Completion::METHOD => {
+            let params = serde_json::from_value::<CompletionParams>(request.params)?;
+            let position = params.text_document_position;
+            let items = documents
+                .get(&position.text_document.uri)
+                .map(|document| completions(document, position.position))
+                .unwrap_or_default();
+            serde_json::to_value(CompletionResponse::Array(items))?
+        }
+        HoverRequest::METHOD => {
+            let params = serde_json::from_value::<HoverParams>(request.params)?;
+            let position = params.text_document_position_params;
+            let hover = documents
+                .get(&position.text_document.uri)
+                .and_then(|document| hover(document, position.position));
+            serde_json::to_value(hover)?
+        }
+        GotoDefinition::METHOD => {
+            let params = serde_json::from_value::<GotoDefinitionParams>(request.params)?;
+            let position = params.text_document_position_params;
+            let uri = position.text_document.uri;
+            let definition = documents.get(&uri).and_then(|document| {
+                let range = definition(document, position.position)?;
+                Some(GotoDefinitionResponse::Scalar(Location::new(
+                    uri.clone(),
+                    range,
+                )))
+            });
+            serde_json::to_value(definition)?
+        }
+        method => return Ok(error_response(id, method)),
+    };
+    Ok(Response::new_ok(id, result))
+}
+
+fn error_response(id: RequestId, method: &str) -> Response {
+    Response::new_err(
+        id,
+        lsp_server::ErrorCode::MethodNotFound as i32,
+        format!("unsupported method {method}"),
+    )
+}
+
+fn diagnostics(text: &str, errors: &[Rich<'_, char>]) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .map(|error| Diagnostic {
+            range: range(text, *error.span()),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some(String::from("hledger-lsp")),
+            message: error.to_string(),
+            ..Diagnostic::default()
+        })
+        .collect()
+}
+
+// account names on posting lines, payees on transaction lines
+fn completions(document: &Document, position: Position) -> Vec<CompletionItem> {
+    let directives = directives(document);
+    let offset = offset(&document.text, position);
+    let line_start = document.text[..offset]
+        .rfind('\n')
+        .map_or(0, |index| index + 1);
+    let line = &document.text[line_start..offset];
+
+    if line.starts_with([' ', '\t']) {
+        let prefix = line.trim_start().trim_start_matches(['(', '[']);
+        return AccountTree::from_directives(&directives)
+            .starting_with(prefix)
+            .into_iter()
+            .map(|node| CompletionItem {
+                label: node.account_name.0.join(":"),
+                kind: Some(CompletionItemKind::FIELD),
+                ..CompletionItem::default()
+            })
+            .collect();
+    }
+    if line.starts_with(|c: char| c.is_ascii_digit()) {
+        let mut payees = directives
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Payee(payee) => Some(payee.0.clone()),
+                Directive::Transaction(transaction) => Some(transaction.payee.clone()),
+                _ => None,
+            })
+            .filter(|payee| !payee.is_empty())
+            .collect::<Vec<_>>();
+        payees.sort();
+        payees.dedup();
+        return payees
+            .into_iter()
+            .map(|payee| CompletionItem {
+                label: payee,
+                kind: Some(CompletionItemKind::VALUE),
+                ..CompletionItem::default()
+            })
+            .collect();
+    }
+    Vec::new()
+}
+
+// balance of the account under the cursor, including its subaccounts
+fn hover(document: &Document, position: Position) -> Option<Hover> {
+    let (account_name, span) = word(&document.text, offset(&document.text, position));
+    let account_name = parse_account_name(account_name);
+    let report = balance(
+        &directives(document),
+        &Options {
+            mode: Mode::Tree,
+            empty: true,
+            ..Options::default()
+        },
+    )
+    .ok()?;
+    let row = report
+        .rows
+        .iter()
+        .find(|row| row.account_name == account_name)?;
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::PlainText,
+            value: format!("{}: {}", account_name.0.join(":"), row.total),
+        }),
+        range: Some(range(&document.text, span)),
+    })
+}
+
+// account or commodity directive declaring the account or commodity under the cursor
+fn definition(document: &Document, position: Position) -> Option<Range> {
+    let (word, _) = word(&document.text, offset(&document.text, position));
+    let account_name = parse_account_name(word);
+    let commodity = word
+        .trim_matches(|c: char| c == '-' || c.is_ascii_digit() || c == '.' || c == ',')
+        .trim_matches('"');
+    document
+        .directives
+        .iter()
+        .find_map(|(directive, span)| match directive {
+            Directive::Account(account) if account.account_name == account_name => Some(*span),
+            Directive::Commodity(declared)
+                if declared.commodity() == &hledger_parser::Commodity::from_str(commodity) =>
+            {
+                Some(*span)
+            }
+            _ => None,
+        })
+        .map(|span| range(&document.text, span))
+}
+
+fn directives(document: &Document) -> Vec<Directive> {
+    document
+        .directives
+        .iter()
+        .map(|(directive, _)| directive.clone())
+        .collect()
+}
+
+fn parse_account_name(word: &str) -> AccountName {
+    AccountName::from_strs(
+        &word
+            .trim_matches(['(', ')', '[', ']'])
+            .split(':')
+            .map(String::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+// text around the offset up to whitespace, with its span
+fn word(text: &str, offset: usize) -> (&str, SimpleSpan) {
+    let start = text[..offset]
+        .char_indices()
+        .rfind(|(_, c)| c.is_whitespace())
+        .map_or(0, |(index, c)| index + c.len_utf8());
+    let end = text[offset..]
+        .find(char::is_whitespace)
+        .map_or(text.len(), |index| offset + index);
+    (&text[start..end], SimpleSpan::new(start, end))
+}
+
+// byte offset of a position, whose character is counted in utf-16 code units
+fn offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(index) => line_start += index + 1,
+            None => return text.len(),
+        }
+    }
+    let mut units = 0;
+    for (index, c) in text[line_start..].char_indices() {
+        if units >= position.character as usize || c == '\n' {
+            return line_start + index;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+fn position(text: &str, offset: usize) -> Position {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    Position::new(
+        u32::try_from(before.matches('\n').count()).unwrap_or(u32::MAX),
+        u32::try_from(before[line_start..].encode_utf16().count()).unwrap_or(u32::MAX),
+    )
+}
+
+fn range(text: &str, span: SimpleSpan) -> Range {
+    Range::new(position(text, span.start), position(text, span.end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        Document {
+            text: text.to_string(),
+            directives: hledger_parser::parse_spanned(text).unwrap(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    const JOURNAL: &str = "account assets:bank
+commodity €
+payee shop
+2024-01-01 shop
+    expenses:food  €10
+    assets:bank
+";
+
+    #[test]
+    fn positions() {
+        let text = "é\nab€c";
+        assert_eq!(position(text, 8), Position::new(1, 3));
+        // inside é
+        assert_eq!(position(text, 1), Position::new(0, 0));
+        assert_eq!(offset(text, Position::new(1, 3)), 8);
+        assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    }
+
+    #[test]
+    fn word_after_multibyte_whitespace() {
+        let text = "expenses:food\u{a0}assets:bank";
+        assert_eq!(word(text, 20), ("assets:bank", SimpleSpan::new(15, 26)));
+        assert_eq!(word(text, 3), ("expenses:food", SimpleSpan::new(0, 13)));
+    }
+
+    #[test]
+    fn diagnostics_of_latest_text() {
+        let mut documents = HashMap::new();
+        let uri = Url::parse("file:///journal").unwrap();
+        let notification = |text: &str| {
+            Notification::new(
+                DidOpenTextDocument::METHOD.to_string(),
+                lsp_types::DidOpenTextDocumentParams {
+                    text_document: lsp_types::TextDocumentItem::new(
+                        uri.clone(),
+                        String::from("hledger"),
+                        0,
+                        text.to_string(),
+                    ),
+                },
+            )
+        };
+        handle_notification(&mut documents, notification("2024-13-01\n")).unwrap();
+        let document = &documents[&uri];
+        assert_eq!(document.diagnostics.len(), 1);
+        assert_eq!(document.diagnostics[0].range.start, Position::new(0, 5));
+        handle_notification(&mut documents, notification(JOURNAL)).unwrap();
+        assert!(documents[&uri].diagnostics.is_empty());
+    }
+
+    #[test]
+    fn account_and_payee_completions() {
+        let text = format!("{JOURNAL}2024-01-02 s\n    ex");
+        let document = Document {
+            directives: hledger_parser::parse_spanned(JOURNAL).unwrap(),
+            diagnostics: Vec::new(),
+            text,
+        };
+        let labels = |line, character| {
+            completions(&document, Position::new(line, character))
+                .into_iter()
+                .map(|item| item.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(7, 6), vec!["expenses", "expenses:food"]);
+        assert_eq!(labels(6, 12), vec!["shop"]);
+    }
+
+    #[test]
+    fn hover_and_definition() {
+        let document = document(JOURNAL);
+        let hover = hover(&document, Position::new(5, 8)).unwrap();
+        assert_eq!(
+            hover.contents,
+            HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::PlainText,
+                value: String::from("assets:bank: €-10"),
+            })
+        );
+        assert_eq!(
+            definition(&document, Position::new(5, 8)),
+            Some(Range::new(Position::new(0, 0), Position::new(0, 19)))
+        );
+        assert_eq!(
+            definition(&document, Position::new(4, 20)),
+            Some(Range::new(Position::new(1, 0), Position::new(1, 11)))
+        );
+    }
+
+    #[test]
+    fn definition_after_failed_edit() {
+        let mut documents =
+            HashMap::from([(Url::parse("file:///journal").unwrap(), document(JOURNAL))]);
+        let uri = Url::parse("file:///journal").unwrap();
+        // a line of multibyte characters above the declarations, which does not parse
+        let text = format!("€€€€€€€€€€\n{JOURNAL}");
+        let notification = Notification::new(
+            DidChangeTextDocument::METHOD.to_string(),
+            lsp_types::DidChangeTextDocumentParams {
+                text_document: lsp_types::VersionedTextDocumentIdentifier::new(uri.clone(), 1),
+                content_changes: vec![lsp_types::TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text,
+                }],
+            },
+        );
+        handle_notification(&mut documents, notification).unwrap();
+        let document = &documents[&uri];
+        assert_eq!(document.diagnostics.len(), 1);
+        assert_eq!(
+            definition(document, Position::new(6, 8)),
+            Some(Range::new(Position::new(1, 0), Position::new(1, 19)))
+        );
+        assert_eq!(
+            definition(document, Position::new(5, 20)),
+            Some(Range::new(Position::new(2, 0), Position::new(2, 11)))
+        );
+    }
+}
@@ -1,16 +1,44 @@
 use ariadne::{Color, Config, IndexType, Label, Report, ReportKind, Source};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
 struct Cli {
     #[arg(long, env)]
     ledger_file: std::path::PathBuf,
+    #[arg(long)]
+    alias: Vec<hledger_parser::Alias>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "List pairs of transactions that are likely duplicates")]
+    Duplicates {
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Largest number of days between duplicates"
+        )]
+        days: u32,
+        #[arg(
+            long,
+            default_value_t = 0.5,
+            help = "Smallest similarity score, from 0 to 1"
+        )]
+        min_score: f64,
+    },
+    #[command(about = "Rewrite the ledger file in a canonical layout")]
+    Fmt {
+        #[arg(long, help = "Only check that the ledger file is formatted")]
+        check: bool,
+    },
 }
 
 #[allow(clippy::missing_panics_doc)]
 pub fn main() {
     let cli = Cli::parse();
-    let contents = match std::fs::read_to_string(cli.ledger_file) {
+    let contents = match std::fs::read_to_string(&cli.ledger_file) {
         Ok(contents) => contents,
         Err(error) => {
             println!("{error}");
@@ -18,13 +46,42 @@ pub fn main() {
         }
     };
 
-    let result = hledger_parser::parse(&contents);
-    match result {
-        Ok(directives) => {
+    let result = hledger_parser::parse_with_aliases(&contents, &cli.alias);
+    match (result, cli.command) {
+        (Ok(directives), None) => {
             println!("{directives:#?}");
             std::process::exit(1);
         }
-        Err(errs) => {
+        (Ok(directives), Some(Command::Duplicates { days, min_score })) => {
+            let options = hledger_parser::duplicates::Options { days, min_score };
+            for duplicate in hledger_parser::duplicates::duplicates(&directives, &options) {
+                println!(
+                    "{:.2}  {} {}  {} {}",
+                    duplicate.score,
+                    duplicate.first.date,
+                    duplicate.first.payee,
+                    duplicate.second.date,
+                    duplicate.second.payee
+                );
+            }
+        }
+        (Ok(_), Some(Command::Fmt { check })) => {
+            let Ok(formatted) = hledger_parser::format(&contents) else {
+                std::process::exit(1);
+            };
+            if formatted == contents {
+                return;
+            }
+            if check {
+                eprintln!("{} is not formatted", cli.ledger_file.display());
+                std::process::exit(1);
+            }
+            if let Err(error) = std::fs::write(&cli.ledger_file, formatted) {
+                println!("{error}");
+                std::process::exit(1);
+            }
+        }
+        (Err(errs), _) => {
             for err in errs {
                 Report::build(ReportKind::Error, (), err.span().start)
                     .with_code(3)
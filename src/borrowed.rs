@@ -0,0 +1,96 @@
+use std::borrow::Cow;
+
+use chumsky::prelude::*;
+
+use crate::directive::directives;
+use crate::state::State;
+
+// directives that borrow their text from the journal where it is used as written, and own it only
+// where it was rewritten, like account names under apply account. into_owned converts them to the
+// directives crate::parse returns.
+pub type Directive<'a> = crate::directive::Directive<Cow<'a, str>>;
+pub type Transaction<'a> = crate::directive::transaction::Simple<Cow<'a, str>>;
+pub type Posting<'a> = crate::directive::transaction::posting::Posting<Cow<'a, str>>;
+pub type AccountName<'a> = crate::AccountName<Cow<'a, str>>;
+pub type Amount<'a> = crate::Amount<Cow<'a, str>>;
+pub type Commodity<'a> = crate::Commodity<Cow<'a, str>>;
+
+// same as crate::parse, without copying the text of directives
+#[allow(clippy::missing_errors_doc)]
+pub fn parse(contents: &str) -> Result<Vec<Directive<'_>>, Vec<Rich<'_, char, SimpleSpan>>> {
+    directives()
+        .then_ignore(end())
+        .parse_with_state(contents, &mut State::default())
+        .into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHEATSHEET_JOURNAL: &str = include_str!("../examples/fixture/cheatsheet.journal");
+
+    #[test]
+    fn same_as_parse() {
+        let borrowed = parse(CHEATSHEET_JOURNAL)
+            .unwrap()
+            .into_iter()
+            .map(Directive::into_owned)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            format!("{borrowed:?}"),
+            format!("{:?}", crate::parse(CHEATSHEET_JOURNAL).unwrap())
+        );
+    }
+
+    #[test]
+    fn borrowed_text() {
+        let directives = parse(
+            "payee shop
+commodity \"green apples\"
+2024-01-05 * (12) shop | groceries
+    ; note
+    expenses:food  10 \"green apples\"
+apply account business
+2024-01-06 rent
+    expenses:rent  $500  ; first
+      ; second
+    assets:bank
+",
+        )
+        .unwrap();
+        let is_borrowed = |text: &Cow<str>| matches!(text, Cow::Borrowed(_));
+        let Directive::Payee(payee) = &directives[0] else {
+            panic!("expected payee");
+        };
+        assert!(is_borrowed(&payee.0));
+
+        let Directive::Transaction(shop) = &directives[2] else {
+            panic!("expected transaction");
+        };
+        assert_eq!(shop.code.as_deref(), Some("12"));
+        assert!(shop.code.iter().chain([&shop.payee]).all(is_borrowed));
+        assert!(shop.description.iter().all(is_borrowed));
+        assert!(is_borrowed(&shop.comment.as_ref().unwrap().0));
+        let posting = &shop.postings[0];
+        assert!(posting.account_name.0.iter().all(is_borrowed));
+        let commodity = &posting.amount.as_ref().unwrap().commodity;
+        assert_eq!(commodity.to_string(), "\"green apples\"");
+        assert_eq!(
+            commodity.clone().into_owned(),
+            crate::Commodity::from_str("green apples")
+        );
+
+        let Directive::Transaction(rent) = &directives[4] else {
+            panic!("expected transaction");
+        };
+        let posting = &rent.postings[0];
+        assert!(!is_borrowed(&posting.account_name.0[0]));
+        assert!(is_borrowed(&posting.account_name.0[1]));
+        assert_eq!(
+            posting.comment.as_ref().map(|comment| comment.0.as_ref()),
+            Some(" first\n second")
+        );
+        assert!(!is_borrowed(&posting.comment.as_ref().unwrap().0));
+    }
+}
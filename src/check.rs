@@ -0,0 +1,459 @@
+use std::collections::{HashMap, HashSet};
+
+use chumsky::span::SimpleSpan;
+
+use crate::component::account_name::AccountName;
+use crate::component::comment::Comment;
+use crate::component::commodity::Commodity;
+use crate::component::price::Price;
+use crate::conversion::balancing_cost;
+use crate::directive::commodity::Commodity as CommodityDirective;
+use crate::directive::Directive;
+use crate::mixed_amount::MixedAmount;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Check {
+    // every account used in a posting is declared with an account directive
+    Accounts,
+    // every commodity used in an amount or price is declared with a commodity directive
+    Commodities,
+    // every transaction's payee is declared with a payee directive
+    Payees,
+    // every tag in a transaction or posting comment is declared with a tag directive
+    Tags,
+    // transactions are ordered by date
+    OrderedDates,
+    // no two accounts have the same last name part
+    UniqueLeafNames,
+    // transactions balance without converting between commodities that have no explicit price
+    BalancedNoAutoConversion,
+}
+
+impl std::str::FromStr for Check {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "accounts" => Ok(Check::Accounts),
+            "commodities" => Ok(Check::Commodities),
+            "payees" => Ok(Check::Payees),
+            "tags" => Ok(Check::Tags),
+            "ordereddates" => Ok(Check::OrderedDates),
+            "uniqueleafnames" => Ok(Check::UniqueLeafNames),
+            "balancednoautoconversion" => Ok(Check::BalancedNoAutoConversion),
+            _ => Err(format!("unknown check: {s}")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub check: Check,
+    // span of the directive that failed the check
+    pub span: SimpleSpan,
+    pub message: String,
+}
+
+// runs the checks over directives parsed with their spans, reporting failures in journal order
+#[must_use]
+pub fn check(directives: &[(Directive, SimpleSpan)], checks: &[Check]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for check in checks {
+        match check {
+            Check::Accounts => accounts(directives, &mut diagnostics),
+            Check::Commodities => commodities(directives, &mut diagnostics),
+            Check::Payees => payees(directives, &mut diagnostics),
+            Check::Tags => tags(directives, &mut diagnostics),
+            Check::OrderedDates => ordered_dates(directives, &mut diagnostics),
+            Check::UniqueLeafNames => unique_leaf_names(directives, &mut diagnostics),
+            Check::BalancedNoAutoConversion => balanced(directives, &mut diagnostics),
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+// account names used by postings of the directive
+fn used_accounts(directive: &Directive) -> Vec<&AccountName> {
+    match directive {
+        Directive::Transaction(transaction) => transaction
+            .postings
+            .iter()
+            .map(|posting| &posting.account_name)
+            .collect(),
+        Directive::PeriodicTransaction(periodic) => periodic
+            .postings
+            .iter()
+            .map(|posting| &posting.account_name)
+            .collect(),
+        Directive::AutoPostings(auto_postings) => auto_postings
+            .postings
+            .iter()
+            .map(|posting| &posting.account_name)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn accounts(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let declared = directives
+        .iter()
+        .filter_map(|(directive, _)| match directive {
+            Directive::Account(account) => Some(&account.account_name),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for (directive, span) in directives {
+        let mut reported = HashSet::new();
+        for account_name in used_accounts(directive) {
+            if !declared.contains(account_name) && reported.insert(account_name) {
+                diagnostics.push(Diagnostic {
+                    check: Check::Accounts,
+                    span: *span,
+                    message: format!("undeclared account {}", account_name.0.join(":")),
+                });
+            }
+        }
+    }
+}
+
+// commodities of amounts, lot costs, prices and assertions used by the directive
+fn used_commodities(directive: &Directive) -> Vec<&Commodity> {
+    fn price_amount(price: &Price) -> &Commodity {
+        match price {
+            Price::Unit(amount) | Price::Total(amount) => &amount.commodity,
+        }
+    }
+    match directive {
+        Directive::Transaction(transaction) => transaction
+            .postings
+            .iter()
+            .flat_map(|posting| {
+                let amount = posting.amount.iter().map(|amount| &amount.commodity);
+                let lot = posting
+                    .lot
+                    .iter()
+                    .filter_map(|lot| lot.cost.as_ref())
+                    .map(price_amount);
+                let price = posting.price.iter().map(price_amount);
+                let assertion = posting
+                    .assertion
+                    .iter()
+                    .map(|assertion| &assertion.amount.commodity);
+                amount.chain(lot).chain(price).chain(assertion)
+            })
+            .collect(),
+        Directive::Price(price) => vec![&price.commodity, &price.amount.commodity],
+        _ => Vec::new(),
+    }
+}
+
+fn commodities(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let declared = directives
+        .iter()
+        .filter_map(|(directive, _)| match directive {
+            Directive::Commodity(CommodityDirective::Amount(amount)) => Some(&amount.commodity),
+            Directive::Commodity(CommodityDirective::Commodity(commodity)) => Some(commodity),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for (directive, span) in directives {
+        let mut reported = HashSet::new();
+        for commodity in used_commodities(directive) {
+            // amounts without commodity symbol need no declaration
+            if *commodity == Commodity::default() {
+                continue;
+            }
+            if !declared.contains(commodity) && reported.insert(commodity) {
+                diagnostics.push(Diagnostic {
+                    check: Check::Commodities,
+                    span: *span,
+                    message: format!("undeclared commodity {commodity}"),
+                });
+            }
+        }
+    }
+}
+
+fn payees(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let declared = directives
+        .iter()
+        .filter_map(|(directive, _)| match directive {
+            Directive::Payee(payee) => Some(payee.0.as_str()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for (directive, span) in directives {
+        let Directive::Transaction(transaction) = directive else {
+            continue;
+        };
+        if !declared.contains(transaction.payee.as_str()) {
+            diagnostics.push(Diagnostic {
+                check: Check::Payees,
+                span: *span,
+                message: format!("undeclared payee {}", transaction.payee),
+            });
+        }
+    }
+}
+
+fn tags(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let declared = directives
+        .iter()
+        .filter_map(|(directive, _)| match directive {
+            Directive::Tag(tag) => Some(tag.0.as_str()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    for (directive, span) in directives {
+        let Directive::Transaction(transaction) = directive else {
+            continue;
+        };
+        let comments = transaction.comment.iter().chain(
+            transaction
+                .postings
+                .iter()
+                .filter_map(|p| p.comment.as_ref()),
+        );
+        let mut reported = HashSet::new();
+        for (tag, _) in comments.flat_map(Comment::tags) {
+            if !declared.contains(tag.as_str()) && reported.insert(tag.clone()) {
+                diagnostics.push(Diagnostic {
+                    check: Check::Tags,
+                    span: *span,
+                    message: format!("undeclared tag {tag}"),
+                });
+            }
+        }
+    }
+}
+
+fn ordered_dates(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let mut latest = None;
+    for (directive, span) in directives {
+        let Directive::Transaction(transaction) = directive else {
+            continue;
+        };
+        match latest {
+            Some(latest) if transaction.date < latest => diagnostics.push(Diagnostic {
+                check: Check::OrderedDates,
+                span: *span,
+                message: format!(
+                    "transaction date {} is before the previous transaction's {latest}",
+                    transaction.date
+                ),
+            }),
+            _ => latest = Some(transaction.date),
+        }
+    }
+}
+
+fn unique_leaf_names(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let mut leaves = HashMap::<&String, &AccountName>::new();
+    for (directive, span) in directives {
+        let account_names = match directive {
+            Directive::Account(account) => vec![&account.account_name],
+            directive => used_accounts(directive),
+        };
+        for account_name in account_names {
+            let Some(leaf) = account_name.0.last() else {
+                continue;
+            };
+            let first = *leaves.entry(leaf).or_insert(account_name);
+            if first != account_name {
+                diagnostics.push(Diagnostic {
+                    check: Check::UniqueLeafNames,
+                    span: *span,
+                    message: format!(
+                        "account {} has the same leaf name as {}",
+                        account_name.0.join(":"),
+                        first.0.join(":")
+                    ),
+                });
+            }
+        }
+    }
+}
+
+fn balanced(directives: &[(Directive, SimpleSpan)], diagnostics: &mut Vec<Diagnostic>) {
+    let mut has_default_account = false;
+    for (directive, span) in directives {
+        let transaction = match directive {
+            Directive::DefaultAccount(_) => {
+                has_default_account = true;
+                continue;
+            }
+            Directive::Transaction(transaction) => transaction,
+            _ => continue,
+        };
+        // the default account takes what is left of a transaction with a single posting
+        if has_default_account && transaction.postings.len() == 1 {
+            continue;
+        }
+        let real = transaction
+            .postings
+            .iter()
+            .filter(|posting| !posting.is_virtual)
+            .collect::<Vec<_>>();
+        // an amountless posting takes whatever is left, in every commodity
+        if real.iter().any(|posting| posting.amount.is_none()) {
+            continue;
+        }
+        let mut balance = MixedAmount::default();
+        let sum = real.iter().try_for_each(|posting| match &posting.amount {
+            Some(amount) => balance.add(&balancing_cost(
+                amount,
+                posting.lot.as_ref(),
+                posting.price.as_ref(),
+            )?),
+            None => Ok(()),
+        });
+        let message = match sum {
+            Err(overflow) => format!("transaction can't be balanced: {overflow}"),
+            Ok(()) if !balance.is_zero() => format!("transaction is unbalanced by {balance}"),
+            Ok(()) => continue,
+        };
+        diagnostics.push(Diagnostic {
+            check: Check::BalancedNoAutoConversion,
+            span: *span,
+            message,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(journal: &str, checks: &[Check]) -> Vec<(usize, String)> {
+        let directives = crate::parse_spanned(journal).unwrap();
+        check(&directives, checks)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.span.start, diagnostic.message))
+            .collect()
+    }
+
+    #[test]
+    fn declarations() {
+        let journal = "account assets:bank
+commodity $
+payee shop
+tag trip
+2024-01-01 shop  ; trip: paris
+    expenses:food  $10  ; meal: lunch
+    assets:bank
+2024-01-02 cafe
+    assets:bank  5 EUR
+    assets:bank
+";
+        assert_eq!(
+            messages(journal, &[Check::Accounts]),
+            vec![(52, String::from("undeclared account expenses:food"))]
+        );
+        assert_eq!(
+            messages(journal, &[Check::Commodities]),
+            vec![(137, String::from("undeclared commodity EUR"))]
+        );
+        assert_eq!(
+            messages(journal, &[Check::Payees]),
+            vec![(137, String::from("undeclared payee cafe"))]
+        );
+        assert_eq!(
+            messages(journal, &[Check::Tags]),
+            vec![(52, String::from("undeclared tag meal"))]
+        );
+    }
+
+    #[test]
+    fn ordered_dates_and_leaf_names() {
+        let journal = "2024-01-02
+    assets:cash  $1
+    income:cash
+2024-01-01
+    expenses:food  $1
+    assets:cash
+";
+        assert_eq!(
+            messages(
+                journal,
+                &[Check::OrderedDates, Check::UniqueLeafNames]
+            ),
+            vec![
+                (
+                    0,
+                    String::from("account income:cash has the same leaf name as assets:cash")
+                ),
+                (
+                    47,
+                    String::from(
+                        "transaction date 2024-01-01 is before the previous transaction's 2024-01-02"
+                    )
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn auto_conversion() {
+        let journal = "2024-01-01
+    assets:euro  10 EUR
+    assets:bank  $-11
+2024-01-02
+    assets:euro  10 EUR @ $1.1
+    assets:bank  $-11
+2024-01-03
+    assets:euro  10 EUR
+    assets:bank
+";
+        assert_eq!(
+            messages(journal, &[Check::BalancedNoAutoConversion]),
+            vec![(0, String::from("transaction is unbalanced by $-11, 10 EUR"))]
+        );
+        assert!("unknown".parse::<Check>().is_err());
+        assert_eq!("tags".parse(), Ok(Check::Tags));
+    }
+
+    #[test]
+    fn default_account() {
+        let journal = "2024-01-01
+    income:salary  $-10
+bucket assets:checking
+2024-01-02
+    income:salary  $-10
+";
+        assert_eq!(
+            messages(journal, &[Check::BalancedNoAutoConversion]),
+            vec![(0, String::from("transaction is unbalanced by $-10"))]
+        );
+    }
+
+    #[test]
+    fn sale_of_lot() {
+        let journal = "2024-01-01
+    assets:stocks  -4 AAPL {$50} @ $60
+    assets:cash  $240
+2024-01-02
+    assets:stocks  -4 AAPL {$50} @ $60
+    assets:cash  $200
+";
+        assert_eq!(
+            messages(journal, &[Check::BalancedNoAutoConversion]),
+            vec![(72, String::from("transaction is unbalanced by $-40"))]
+        );
+    }
+
+    #[test]
+    fn price_overflow() {
+        let journal = "2024-01-01 x
+    a  10000000000 X @ 10000000000000000000 Y
+    b  -10000000000 X
+";
+        assert_eq!(
+            messages(journal, &[Check::BalancedNoAutoConversion]),
+            vec![(
+                0,
+                String::from("transaction can't be balanced: amount is too large")
+            )]
+        );
+    }
+}
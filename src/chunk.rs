@@ -0,0 +1,92 @@
+use std::ops::Range;
+
+use chumsky::prelude::*;
+
+use crate::directive::{directives, Directive};
+use crate::state::State;
+
+// parse error with its message, independent of the parsed text
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub span: SimpleSpan,
+    pub message: String,
+}
+
+// byte ranges of top-level chunks: a line that is not indented with the indented lines after it,
+// or a whole comment block. a chunk ends after the newline of its last line, so that the chunks
+// cover the whole text and each one parses on its own.
+pub(crate) fn chunks(text: &str) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut in_block = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end();
+        if in_block {
+            in_block = content != "end comment";
+        } else if starts_chunk(line) {
+            if offset > start {
+                chunks.push(start..offset);
+            }
+            start = offset;
+            in_block = content == "comment";
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        chunks.push(start..offset);
+    }
+    chunks
+}
+
+// whether a new chunk starts at the line, which is the case for lines that are not indented,
+// and not empty
+pub(crate) fn starts_chunk(line: &str) -> bool {
+    !line.starts_with([' ', '\t', '\n', '\r'])
+}
+
+// directives of a chunk starting at the offset in the journal, and its errors with spans in the
+// journal. the state is updated by the chunk's directives.
+pub(crate) fn parse_chunk(
+    chunk: &str,
+    offset: usize,
+    state: &mut State,
+) -> (Vec<Directive>, Vec<ParseError>) {
+    let (directives, errors) = directives()
+        .then_ignore(end())
+        .parse_with_state(chunk, state)
+        .into_output_errors();
+    let errors = errors
+        .into_iter()
+        .map(|error| ParseError {
+            span: SimpleSpan::new(error.span().start + offset, error.span().end + offset),
+            message: error.to_string(),
+        })
+        .collect();
+    (directives.unwrap_or_default(), errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let text =
+            "; header\n\n2024-01-01\n    a  $1\n    b\n\ncomment\nx\n\nend comment\nY 2024\n";
+        let parts = chunks(text)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            vec![
+                "; header\n\n",
+                "2024-01-01\n    a  $1\n    b\n\n",
+                "comment\nx\n\nend comment\n",
+                "Y 2024\n"
+            ]
+        );
+        assert_eq!(chunks(""), Vec::<Range<usize>>::new());
+    }
+}
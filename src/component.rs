@@ -3,6 +3,7 @@ pub mod amount;
 pub mod comment;
 pub mod commodity;
 pub mod date;
+pub mod lot;
 pub mod period;
 pub mod price;
 pub mod quantity;
@@ -1,18 +1,36 @@
 use chumsky::prelude::*;
 
 use crate::state::State;
+use crate::text::Text;
 
-#[derive(Debug, Clone, PartialEq)]
-pub struct AccountName(pub Vec<String>);
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountName<T = String>(pub Vec<T>);
 
 impl AccountName {
+    #[must_use]
     pub fn from_strs(parts: &[String]) -> Self {
         Self(parts.to_vec())
     }
 }
 
-pub fn account_name<'a>(
-) -> impl Parser<'a, &'a str, AccountName, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> AccountName<T> {
+    #[must_use]
+    pub fn into_owned(self) -> AccountName {
+        AccountName(self.0.into_iter().map(Text::into_string).collect())
+    }
+}
+
+pub fn account_name<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, AccountName<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    raw_account_name().map_with(|account_name, e| {
+        let state: &mut State = e.state();
+        rewrite(account_name, state)
+    })
+}
+
+// account name as written, without parent accounts and aliases applied
+pub fn raw_account_name<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, AccountName<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let part = any()
         .and_is(text::newline().not())
         .and_is(just(":").not()) // forbidden, because it separates account parts
@@ -20,19 +38,51 @@ pub fn account_name<'a>(
         .and_is(just("  ").not()) // forbidden, because it separates inline account comment
         .repeated()
         .at_least(1)
-        .collect::<String>();
+        .to_slice()
+        .map(|part: &str| T::from_slice(part.trim()));
     part.separated_by(just(":"))
         .at_least(1)
         .collect::<Vec<_>>()
-        .map(|parts| {
-            AccountName::from_strs(
-                &parts
-                    .iter()
-                    .map(|s| s.trim())
-                    .map(std::string::ToString::to_string)
-                    .collect::<Vec<String>>(),
-            )
-        })
+        .map(AccountName)
+}
+
+// prefixes parent accounts, then applies aliases: most recently defined directives first, then
+// options
+fn rewrite<'a, T: Text<'a>>(account_name: AccountName<T>, state: &State) -> AccountName<T> {
+    let account_name = if state.parent_accounts.is_empty() {
+        account_name
+    } else {
+        AccountName(
+            state
+                .parent_accounts
+                .iter()
+                .flat_map(|parent| parent.0.iter())
+                .map(|part| T::from_string(part.clone()))
+                .chain(account_name.0)
+                .collect(),
+        )
+    };
+    if state.aliases.is_empty() && state.option_aliases.is_empty() {
+        return account_name;
+    }
+    let name = account_name
+        .0
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join(":");
+    let rewritten = state
+        .aliases
+        .iter()
+        .rev()
+        .chain(state.option_aliases.iter())
+        .fold(name, |name, alias| alias.apply(&name));
+    AccountName(
+        rewritten
+            .split(':')
+            .map(|part| T::from_string(part.trim().to_string()))
+            .collect(),
+    )
 }
 
 #[cfg(test)]
@@ -48,6 +98,54 @@ mod tests {
         assert_eq!(result, Ok(AccountName(vec![String::from("account")])));
     }
 
+    #[test]
+    fn aliased() {
+        let mut state = State {
+            option_aliases: vec!["/food/ = groceries".parse().unwrap()],
+            ..State::default()
+        };
+        state
+            .aliases
+            .push("expenses = spending:out".parse().unwrap());
+        let result = account_name()
+            .then_ignore(end())
+            .parse_with_state("expenses:food", &mut state)
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(AccountName(vec![
+                String::from("spending"),
+                String::from("out"),
+                String::from("groceries"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn prefixed_then_aliased() {
+        let mut state = State {
+            parent_accounts: vec![
+                AccountName(vec![String::from("personal")]),
+                AccountName(vec![String::from("expenses")]),
+            ],
+            ..State::default()
+        };
+        state
+            .aliases
+            .push("personal:expenses = spending".parse().unwrap());
+        let result = account_name()
+            .then_ignore(end())
+            .parse_with_state("food", &mut state)
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(AccountName(vec![
+                String::from("spending"),
+                String::from("food"),
+            ]))
+        );
+    }
+
     #[test]
     fn ok_complex() {
         let result = account_name()
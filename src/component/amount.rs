@@ -1,76 +1,177 @@
 use chumsky::prelude::*;
+use rust_decimal::Decimal;
 
+pub mod style;
+
+use crate::component::amount::style::{CommodityStyle, Side};
 use crate::component::commodity::{commodity, Commodity};
 use crate::component::quantity::{quantity, Quantity};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 
-#[derive(Debug, Default, Clone, PartialEq)]
-pub struct Amount {
+#[derive(Debug, Default, Clone)]
+pub struct Amount<T = String> {
     pub is_negative: bool,
     pub quantity: Quantity,
-    pub commodity: Commodity,
+    pub commodity: Commodity<T>,
+    pub style: CommodityStyle,
+}
+
+// style only affects how an amount is displayed, so it is not compared
+impl<T: PartialEq> PartialEq for Amount<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_negative == other.is_negative
+            && self.quantity == other.quantity
+            && self.commodity == other.commodity
+    }
+}
+
+impl<'a, T: Text<'a>> Amount<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Amount {
+        Amount {
+            is_negative: self.is_negative,
+            quantity: self.quantity,
+            commodity: self.commodity.into_owned(),
+            style: self.style,
+        }
+    }
+}
+
+impl<T> Amount<T> {
+    #[must_use]
+    pub fn to_decimal(&self) -> Decimal {
+        let value = self.quantity.to_decimal();
+        if self.is_negative {
+            -value
+        } else {
+            value
+        }
+    }
+
+    #[must_use]
+    pub fn from_decimal(value: Decimal, commodity: Commodity<T>, style: CommodityStyle) -> Self {
+        Self {
+            is_negative: value.is_sign_negative() && !value.is_zero(),
+            quantity: Quantity::from_decimal(value),
+            commodity,
+            style,
+        }
+    }
+
+    // same as from_decimal, or None if the value is too large for a quantity
+    #[must_use]
+    pub fn checked_from_decimal(
+        value: Decimal,
+        commodity: Commodity<T>,
+        style: CommodityStyle,
+    ) -> Option<Self> {
+        Some(Self {
+            is_negative: value.is_sign_negative() && !value.is_zero(),
+            quantity: Quantity::checked_from_decimal(value)?,
+            commodity,
+            style,
+        })
+    }
 }
 
-pub fn amount<'a>() -> impl Parser<'a, &'a str, Amount, extra::Full<Rich<'a, char>, State, ()>> {
+impl<T: AsRef<str>> std::fmt::Display for Amount<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.style.format(self))
+    }
+}
+
+pub fn amount<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Amount<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    raw_amount().map_with(|amount: Amount<T>, e| {
+        let state: &mut State = e.state();
+        match &state.default_commodity {
+            // most recent D directive applies to amounts written without commodity
+            Some((commodity, style)) if amount.commodity == Commodity::default() => Amount {
+                commodity: commodity.to_text(),
+                style: CommodityStyle {
+                    precision: style.precision.max(amount.style.precision),
+                    ..style.clone()
+                },
+                ..amount
+            },
+            _ => amount,
+        }
+    })
+}
+
+// amount as written, without default commodity applied
+pub fn raw_amount<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Amount<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    let spaces = || whitespace().repeated().count();
     let sign_quantity_commodity = one_of("-+")
         .then_ignore(whitespace().repeated())
-        .then(quantity())
-        .then_ignore(whitespace().repeated())
+        .then(styled_quantity())
+        .then(spaces())
         .then(commodity())
-        .map(|((sign, quantity), commodity)| Amount {
+        .map(|(((sign, (quantity, style)), spaces), commodity)| Amount {
             quantity,
             commodity,
             is_negative: sign == '-',
+            style: style.placed(Side::Right, spaces),
         });
-    let quantity_sign_commodity = quantity()
-        .then_ignore(whitespace().repeated())
+    let quantity_sign_commodity = styled_quantity()
+        .then(spaces())
         .then(one_of("-+"))
-        .then_ignore(whitespace().repeated())
+        .then(spaces())
         .then(commodity())
-        .map(|((quantity, sign), commodity)| Amount {
-            quantity,
-            commodity,
-            is_negative: sign == '-',
-        });
+        .map(
+            |(((((quantity, style), before), sign), after), commodity)| Amount {
+                quantity,
+                commodity,
+                is_negative: sign == '-',
+                style: style.placed(Side::Right, before + after),
+            },
+        );
     let sign_commodity_quantity = one_of("-+")
         .then_ignore(whitespace().repeated())
         .then(commodity())
-        .then_ignore(whitespace().repeated())
-        .then(quantity())
-        .map(|((sign, commodity), quantity)| Amount {
+        .then(spaces())
+        .then(styled_quantity())
+        .map(|(((sign, commodity), spaces), (quantity, style))| Amount {
             quantity,
             commodity,
             is_negative: sign == '-',
+            style: style.placed(Side::Left, spaces),
         });
     let commodity_sign_quantity = commodity()
-        .then_ignore(whitespace().repeated())
+        .then(spaces())
         .then(one_of("-+"))
-        .then_ignore(whitespace().repeated())
-        .then(quantity())
-        .map(|((commodity, sign), quantity)| Amount {
-            quantity,
-            commodity,
-            is_negative: sign == '-',
-        });
-    let quantity_commodity = quantity()
-        .then_ignore(whitespace().repeated())
-        .then(commodity())
-        .map(|(quantity, commodity)| Amount {
+        .then(spaces())
+        .then(styled_quantity())
+        .map(
+            |((((commodity, before), sign), after), (quantity, style))| Amount {
+                quantity,
+                commodity,
+                is_negative: sign == '-',
+                style: style.placed(Side::Left, before + after),
+            },
+        );
+    let quantity_commodity = styled_quantity().then(spaces()).then(commodity()).map(
+        |(((quantity, style), spaces), commodity)| Amount {
             quantity,
             commodity,
+            style: style.placed(Side::Right, spaces),
             ..Amount::default()
-        });
-    let commodity_quantity = commodity()
-        .then_ignore(whitespace().repeated())
-        .then(quantity())
-        .map(|(commodity, quantity)| Amount {
+        },
+    );
+    let commodity_quantity = commodity().then(spaces()).then(styled_quantity()).map(
+        |((commodity, spaces), (quantity, style))| Amount {
             quantity,
             commodity,
+            style: style.placed(Side::Left, spaces),
             ..Amount::default()
-        });
-    let just_quantity = quantity().map(|quantity| Amount {
+        },
+    );
+    let just_quantity = styled_quantity().map(|(quantity, style)| Amount {
         quantity,
+        style,
         ..Amount::default()
     });
     sign_quantity_commodity
@@ -80,6 +181,16 @@ pub fn amount<'a>() -> impl Parser<'a, &'a str, Amount, extra::Full<Rich<'a, cha
         .or(quantity_commodity)
         .or(commodity_quantity)
         .or(just_quantity)
+        // keeps the types of parsers built on top of it small enough to compile
+        .boxed()
+}
+
+fn styled_quantity<'a>(
+) -> impl Parser<'a, &'a str, (Quantity, CommodityStyle), extra::Full<Rich<'a, char>, State, ()>> {
+    quantity().map_with(|quantity, e| {
+        let style = CommodityStyle::from_quantity(e.slice(), &quantity);
+        (quantity, style)
+    })
 }
 
 #[cfg(test)]
@@ -88,7 +199,10 @@ mod tests {
 
     #[test]
     fn quantity_no_commodity() {
-        let result = amount().then_ignore(end()).parse("1").into_result();
+        let result = amount::<String>()
+            .then_ignore(end())
+            .parse("1")
+            .into_result();
         assert_eq!(
             result,
             Ok(Amount {
@@ -98,6 +212,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn default_commodity() {
+        let mut state = State {
+            default_commodity: Some((
+                Commodity::from_str("$"),
+                raw_amount::<String>()
+                    .parse("$1,000.00")
+                    .into_result()
+                    .unwrap()
+                    .style,
+            )),
+            ..State::default()
+        };
+        let result = amount()
+            .then_ignore(end())
+            .parse_with_state("1234.567", &mut state)
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            result,
+            Amount {
+                quantity: Quantity {
+                    mantissa: 1_234_567,
+                    places: 3,
+                },
+                commodity: Commodity::from_str("$"),
+                ..Amount::default()
+            }
+        );
+        assert_eq!(result.to_string(), "$1,234.567");
+
+        let result = amount()
+            .then_ignore(end())
+            .parse_with_state("2 EUR", &mut state)
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(Amount {
+                quantity: Quantity::from_u64(2),
+                commodity: Commodity::from_str("EUR"),
+                ..Amount::default()
+            })
+        );
+    }
+
     #[test]
     fn quantity_with_commodity() {
         for (input, expected) in [
@@ -140,6 +299,7 @@ mod tests {
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
                     is_negative: true,
+                    ..Amount::default()
                 },
             ),
             (
@@ -148,6 +308,7 @@ mod tests {
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
                     is_negative: true,
+                    ..Amount::default()
                 },
             ),
             (
@@ -164,6 +325,7 @@ mod tests {
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
                     is_negative: true,
+                    ..Amount::default()
                 },
             ),
             (
@@ -172,6 +334,7 @@ mod tests {
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("USD"),
                     is_negative: true,
+                    ..Amount::default()
                 },
             ),
         ] {
@@ -179,4 +342,38 @@ mod tests {
             assert_eq!(result, Ok(expected), "{input}");
         }
     }
+
+    proptest::proptest! {
+        #[test]
+        fn display_round_trip(
+            is_negative: bool,
+            mantissa in 0..1_000_000_000_000_000_u64,
+            places in 0..6_u64,
+            commodity in proptest::sample::select(vec!["", "$", "€", "USD", "VANGUARD 500"]),
+            side in proptest::sample::select(vec![Side::Left, Side::Right]),
+            is_spaced: bool,
+            (digit_group_mark, decimal_mark) in proptest::sample::select(vec![
+                (None, '.'),
+                (Some(','), '.'),
+                (None, ','),
+                (Some('.'), ','),
+            ]),
+        ) {
+            let amount = Amount {
+                is_negative: is_negative && mantissa > 0,
+                quantity: Quantity { mantissa, places },
+                commodity: Commodity::from_str(commodity),
+                style: CommodityStyle {
+                    side,
+                    is_spaced,
+                    digit_group_mark,
+                    decimal_mark: Some(decimal_mark),
+                    precision: places,
+                },
+            };
+            let printed = amount.to_string();
+            let result = raw_amount().then_ignore(end()).parse(&printed).into_result();
+            proptest::prop_assert_eq!(result, Ok(amount), "{}", printed);
+        }
+    }
 }
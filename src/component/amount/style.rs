@@ -0,0 +1,227 @@
+use crate::component::amount::Amount;
+use crate::component::quantity::Quantity;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    // $1
+    #[default]
+    Left,
+    // 1 USD
+    Right,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CommodityStyle {
+    pub side: Side,
+    pub is_spaced: bool,
+    // separates groups of three integer digits, i.e. "," in "1,000.00"
+    pub digit_group_mark: Option<char>,
+    pub decimal_mark: Option<char>,
+    pub precision: u64,
+}
+
+impl CommodityStyle {
+    // reads decimal and digit group marks from the quantity as it was written, i.e. "1.000,00"
+    pub(crate) fn from_quantity(text: &str, quantity: &Quantity) -> Self {
+        let marks = text
+            .chars()
+            .filter(|c| !c.is_ascii_digit())
+            .collect::<Vec<_>>();
+        let has_decimals = quantity.places > 0 || text.ends_with(|c: char| !c.is_ascii_digit());
+        let (decimal_mark, group_marks) = match marks.split_last() {
+            Some((last, rest)) if has_decimals => (Some(*last), rest),
+            _ => (None, marks.as_slice()),
+        };
+        Self {
+            digit_group_mark: group_marks.first().copied(),
+            decimal_mark,
+            precision: quantity.places,
+            ..Self::default()
+        }
+    }
+
+    // commodity position relative to the quantity, and number of spaces between them
+    pub(crate) fn placed(self, side: Side, spaces: usize) -> Self {
+        Self {
+            side,
+            is_spaced: spaces > 0,
+            ..self
+        }
+    }
+
+    // formats amount the way hledger displays it, rounding the quantity to the style's precision
+    #[must_use]
+    pub fn format<T: AsRef<str>>(&self, amount: &Amount<T>) -> String {
+        self.format_with_decimal_mark(amount, None)
+    }
+
+    // formats amount for a journal that declares its decimal mark, where a digit group mark can't
+    // be read as the decimal mark
+    pub(crate) fn format_with_decimal_mark<T: AsRef<str>>(
+        &self,
+        amount: &Amount<T>,
+        declared: Option<char>,
+    ) -> String {
+        let digits = round(&amount.quantity, self.precision).to_string();
+        let precision = usize::try_from(self.precision).unwrap_or(usize::MAX);
+        let digits = format!("{digits:0>width$}", width = precision.saturating_add(1));
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+
+        let mut number = group(integer, self.digit_group_mark);
+        if !fraction.is_empty() {
+            number.push(self.decimal_mark.unwrap_or('.'));
+            number.push_str(fraction);
+        } else if let Some(mark) = self
+            .digit_group_mark
+            .filter(|mark| declared.is_none_or(|declared| declared == *mark))
+            .filter(|_| (4..=6).contains(&integer.len()))
+        {
+            // a single digit group mark reads back as a decimal mark ("1,000" is 1.000),
+            // so a trailing decimal mark keeps the number an integer, as hledger does
+            number.push(if mark == '.' { ',' } else { '.' });
+        }
+        if amount.is_negative && digits.chars().any(|c| c != '0') {
+            number.insert(0, '-');
+        }
+
+        let commodity = amount.commodity.to_string();
+        let space = if self.is_spaced && !commodity.is_empty() {
+            " "
+        } else {
+            ""
+        };
+        match self.side {
+            Side::Left => format!("{commodity}{space}{number}"),
+            Side::Right => format!("{number}{space}{commodity}"),
+        }
+    }
+}
+
+// rescales quantity's mantissa to the given number of decimal places, rounding half to even
+fn round(quantity: &Quantity, precision: u64) -> u128 {
+    let mantissa = u128::from(quantity.mantissa);
+    if precision >= quantity.places {
+        let scale = u32::try_from(precision - quantity.places).unwrap_or(u32::MAX);
+        return 10_u128
+            .checked_pow(scale)
+            .and_then(|factor| mantissa.checked_mul(factor))
+            .unwrap_or(u128::MAX);
+    }
+    let scale = u32::try_from(quantity.places - precision).unwrap_or(u32::MAX);
+    let Some(divisor) = 10_u128.checked_pow(scale) else {
+        return 0;
+    };
+    let (quotient, remainder) = (mantissa / divisor, mantissa % divisor);
+    match (remainder * 2).cmp(&divisor) {
+        std::cmp::Ordering::Less => quotient,
+        std::cmp::Ordering::Greater => quotient + 1,
+        std::cmp::Ordering::Equal => quotient + quotient % 2,
+    }
+}
+
+fn group(integer: &str, mark: Option<char>) -> String {
+    let Some(mark) = mark else {
+        return integer.to_string();
+    };
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i).is_multiple_of(3) {
+            grouped.push(mark);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::prelude::*;
+
+    use crate::component::amount::amount;
+
+    use super::*;
+
+    fn style_of(input: &str) -> CommodityStyle {
+        amount::<String>()
+            .then_ignore(end())
+            .parse(input)
+            .into_result()
+            .unwrap()
+            .style
+    }
+
+    #[test]
+    fn marks() {
+        for (input, digit_group_mark, decimal_mark, precision) in [
+            ("1", None, None, 0),
+            ("1.", None, Some('.'), 0),
+            ("1,00", None, Some(','), 2),
+            ("1,000,000", Some(','), None, 0),
+            ("1.000,00", Some('.'), Some(','), 2),
+            (".5", None, Some('.'), 1),
+        ] {
+            let style = style_of(input);
+            assert_eq!(style.digit_group_mark, digit_group_mark, "{input}");
+            assert_eq!(style.decimal_mark, decimal_mark, "{input}");
+            assert_eq!(style.precision, precision, "{input}");
+        }
+    }
+
+    #[test]
+    fn side_and_spacing() {
+        for (input, side, is_spaced) in [
+            ("$1", Side::Left, false),
+            ("-$ 1", Side::Left, true),
+            ("$ -1", Side::Left, true),
+            ("1USD", Side::Right, false),
+            ("-1 USD", Side::Right, true),
+            ("3 \"green apples\"", Side::Right, true),
+        ] {
+            let style = style_of(input);
+            assert_eq!(style.side, side, "{input}");
+            assert_eq!(style.is_spaced, is_spaced, "{input}");
+        }
+    }
+
+    #[test]
+    fn format_round_trip() {
+        for input in [
+            "$1",
+            "$-1,000.50",
+            "1,000. EUR",
+            "1.000, EUR",
+            "1,000,000 EUR",
+            "1.000,00 EUR",
+            "-10 gold",
+            "3 \"green apples\"",
+            "USD 0.001",
+        ] {
+            let amount = amount::<String>()
+                .then_ignore(end())
+                .parse(input)
+                .into_result();
+            let amount = amount.unwrap();
+            assert_eq!(amount.style.format(&amount), input);
+        }
+    }
+
+    #[test]
+    fn format_rounds_half_to_even() {
+        let style = CommodityStyle {
+            precision: 2,
+            ..style_of("$1,000.00")
+        };
+        for (input, expected) in [
+            ("$1.005", "$1.00"),
+            ("$1.015", "$1.02"),
+            ("$1234.5", "$1,234.50"),
+            ("$-0.001", "$0.00"),
+        ] {
+            let amount = amount::<String>()
+                .then_ignore(end())
+                .parse(input)
+                .into_result();
+            assert_eq!(style.format(&amount.unwrap()), expected, "{input}");
+        }
+    }
+}
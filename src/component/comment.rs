@@ -2,51 +2,84 @@ use chumsky::prelude::*;
 
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Comment(String);
+pub struct Comment<T = String>(pub T);
 
-pub fn line<'a>() -> impl Parser<'a, &'a str, Comment, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Comment<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Comment {
+        Comment(self.0.into_string())
+    }
+}
+
+impl<T: AsRef<str>> Comment<T> {
+    // value of a "name: value" tag, which ends at a comma or end of line
+    #[must_use]
+    pub fn tag(&self, name: &str) -> Option<String> {
+        self.tags()
+            .into_iter()
+            .find_map(|(tag, value)| (tag == name).then_some(value))
+    }
+
+    // names and values of all tags. a tag's name is the word right before its colon.
+    #[must_use]
+    pub fn tags(&self) -> Vec<(String, String)> {
+        self.0
+            .as_ref()
+            .lines()
+            .flat_map(|line| line.split(','))
+            .filter_map(|part| {
+                let (tag, value) = part.split_once(':')?;
+                let tag = tag.split_whitespace().last()?;
+                Some((tag.to_string(), value.trim().to_string()))
+            })
+            .collect()
+    }
+}
+
+pub fn line<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Comment<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("#")
-        .ignore_then(
-            any()
-                .and_is(text::newline().not())
-                .repeated()
-                .collect::<String>(),
-        )
-        .map(Comment)
+        .ignore_then(any().and_is(text::newline().not()).repeated().to_slice())
+        .map(|comment| Comment(T::from_slice(comment)))
 }
 
-pub fn block<'a>() -> impl Parser<'a, &'a str, Comment, extra::Full<Rich<'a, char>, State, ()>> {
+pub fn block<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Comment<T>, extra::Full<Rich<'a, char>, State, ()>> {
     any()
         .and_is(text::newline().not())
         .and_is(just("end comment\n").not())
         .repeated()
-        .collect::<String>()
+        .to_slice()
         .separated_by(text::newline())
         .collect::<Vec<_>>()
         .delimited_by(just("comment\n"), just("end comment\n"))
-        .map(|lines| Comment(lines.join("\n").trim().to_string()))
+        .map(|lines: Vec<&str>| match lines.as_slice() {
+            [line] => Comment(T::from_slice(line.trim())),
+            lines => Comment(T::from_string(lines.join("\n").trim().to_string())),
+        })
 }
 
-pub fn inline<'a>() -> impl Parser<'a, &'a str, Comment, extra::Full<Rich<'a, char>, State, ()>> {
-    let comment = just(";").ignore_then(
-        any()
-            .and_is(text::newline().not())
-            .repeated()
-            .collect::<String>(),
-    );
+pub fn inline<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Comment<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    let comment = just(";").ignore_then(any().and_is(text::newline().not()).repeated().to_slice());
     let prefixed_comment =
         text::newline().ignore_then(whitespace().repeated().at_least(1).ignore_then(comment));
     comment
         .then(prefixed_comment.repeated().collect::<Vec<_>>())
-        .map(|(first, rest)| {
-            Comment(
-                std::iter::once(first)
-                    .chain(rest)
-                    .collect::<Vec<_>>()
-                    .join("\n"),
-            )
+        .map(|(first, rest): (&str, Vec<&str>)| {
+            if rest.is_empty() {
+                Comment(T::from_slice(first))
+            } else {
+                Comment(T::from_string(
+                    std::iter::once(first)
+                        .chain(rest)
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                ))
+            }
         })
 }
 
@@ -54,6 +87,27 @@ pub fn inline<'a>() -> impl Parser<'a, &'a str, Comment, extra::Full<Rich<'a, ch
 mod tests {
     use super::*;
 
+    #[test]
+    fn tags() {
+        let result = inline::<String>()
+            .then_ignore(end())
+            .parse("; note, type: A, date:2024-01-01\n  ; project: x")
+            .into_result()
+            .unwrap();
+        assert_eq!(result.tag("type"), Some(String::from("A")));
+        assert_eq!(result.tag("date"), Some(String::from("2024-01-01")));
+        assert_eq!(result.tag("project"), Some(String::from("x")));
+        assert_eq!(result.tag("note"), None);
+        assert_eq!(
+            result
+                .tags()
+                .into_iter()
+                .map(|(tag, _)| tag)
+                .collect::<Vec<_>>(),
+            vec!["type", "date", "project"]
+        );
+    }
+
     #[test]
     fn ok_line() {
         let result = line().then_ignore(end()).parse("# a comment").into_result();
@@ -89,7 +143,7 @@ mod tests {
 
     #[test]
     fn err() {
-        let result = inline()
+        let result = inline::<String>()
             .then_ignore(end())
             .parse("not a comment")
             .into_result();
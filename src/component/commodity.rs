@@ -1,31 +1,61 @@
 use chumsky::prelude::*;
 
 use crate::state::State;
+use crate::text::Text;
 
-#[derive(Debug, Clone, Default, PartialEq)]
-pub struct Commodity(String);
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Commodity<T = String>(T);
 
 impl Commodity {
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Self {
         Self(s.to_string())
     }
+
+    // same commodity with the text type of the directive being parsed
+    pub(crate) fn to_text<'a, T: Text<'a>>(&self) -> Commodity<T> {
+        Commodity(T::from_string(self.0.clone()))
+    }
+}
+
+impl<'a, T: Text<'a>> Commodity<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Commodity {
+        Commodity(self.0.into_string())
+    }
 }
 
-pub fn commodity<'a>() -> impl Parser<'a, &'a str, Commodity, extra::Full<Rich<'a, char>, State, ()>>
-{
+// prints commodity the way it is written in a journal, quoting it when needed
+impl<T: AsRef<str>> std::fmt::Display for Commodity<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.0.as_ref();
+        let is_symbol = name.chars().count() == 1 && !name.chars().all(char::is_alphabetic);
+        if is_symbol || name.chars().all(char::is_alphabetic) {
+            f.write_str(name)
+        } else {
+            write!(f, "\"{name}\"")
+        }
+    }
+}
+
+pub fn commodity<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Commodity<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let letter = any().filter(|c: &char| c.is_alphabetic());
     let symbol = one_of("$¢€£ƒ₣₧₱₨₹₽₺¥");
 
-    let symbol = symbol.repeated().exactly(1).collect().map(Commodity);
-    let simple = letter.repeated().collect().map(Commodity);
+    let symbol = symbol.repeated().exactly(1).to_slice();
+    let simple = letter.repeated().to_slice();
     let quoted = any()
         .and_is(just("\"").not())
         .repeated()
-        .collect::<String>()
-        .padded_by(just("\""))
-        .map(Commodity);
+        .to_slice()
+        .padded_by(just("\""));
 
-    symbol.or(quoted).or(simple)
+    symbol
+        .or(quoted)
+        .or(simple)
+        .map(|name| Commodity(T::from_slice(name)))
 }
 
 #[cfg(test)]
@@ -69,7 +99,10 @@ mod tests {
 
     #[test]
     pub fn error() {
-        let result = commodity().then_ignore(end()).parse("123").into_errors();
+        let result = commodity::<String>()
+            .then_ignore(end())
+            .parse("123")
+            .into_errors();
         assert!(!result.is_empty());
     }
 }
@@ -46,9 +46,20 @@ pub fn date<'a>(
             .then(month)
             .then_ignore(just(separator))
             .then(day)
-            .map_with(|((year, month), day), e| {
+            .validate(|((year, month), day), e, emitter| {
+                let span = e.span();
                 let state: &mut State = e.state();
-                chrono::NaiveDate::from_ymd_opt(year.unwrap_or(state.year), month, day).unwrap()
+                let year = year.unwrap_or(state.year);
+                chrono::NaiveDate::from_ymd_opt(year, month, day).unwrap_or_else(|| {
+                    // months and days out of range are already reported
+                    if (1..=12).contains(&month) && (1..=31).contains(&day) {
+                        emitter.emit(Rich::custom(
+                            span,
+                            format!("{year}-{month:02}-{day:02} is not a valid date."),
+                        ));
+                    }
+                    chrono::NaiveDate::default()
+                })
             })
     };
     date('/').or(date('.')).or(date('-'))
@@ -86,9 +97,32 @@ mod tests {
         ] {
             let result = date()
                 .then_ignore(end())
-                .parse_with_state(input, &mut State { year: 2011 })
+                .parse_with_state(
+                    input,
+                    &mut State {
+                        year: 2011,
+                        ..State::default()
+                    },
+                )
                 .into_result();
             assert_eq!(result, Ok(expected), "{input}");
         }
     }
+
+    #[test]
+    fn invalid() {
+        for input in ["2023-02-29", "2024-04-31", "02-30"] {
+            let result = date()
+                .then_ignore(end())
+                .parse_with_state(
+                    input,
+                    &mut State {
+                        year: 2023,
+                        ..State::default()
+                    },
+                )
+                .into_result();
+            assert!(result.is_err(), "{input}");
+        }
+    }
 }
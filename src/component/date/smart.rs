@@ -52,20 +52,19 @@ fn periods_ago<'a>(
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("ago"))
         .try_map(|(length, period), span| {
+            let length = length
+                .parse::<u32>()
+                .map_err(|error| Rich::custom(span, error))?;
             match period {
-                Period::Day => {
-                    today().checked_sub_days(chrono::Days::new(length.parse::<u64>().unwrap()))
-                }
-                Period::Week => {
-                    today().checked_sub_days(chrono::Days::new(length.parse::<u64>().unwrap() * 7))
-                }
-                Period::Month => {
-                    today().checked_sub_months(chrono::Months::new(length.parse::<u32>().unwrap()))
-                }
-                Period::Quarter => today()
-                    .checked_sub_months(chrono::Months::new(length.parse::<u32>().unwrap() * 3)),
-                Period::Year => today()
-                    .checked_sub_months(chrono::Months::new(length.parse::<u32>().unwrap() * 12)),
+                Period::Day => today().checked_sub_days(chrono::Days::new(u64::from(length))),
+                Period::Week => today().checked_sub_days(chrono::Days::new(u64::from(length) * 7)),
+                Period::Month => today().checked_sub_months(chrono::Months::new(length)),
+                Period::Quarter => length
+                    .checked_mul(3)
+                    .and_then(|months| today().checked_sub_months(chrono::Months::new(months))),
+                Period::Year => length
+                    .checked_mul(12)
+                    .and_then(|months| today().checked_sub_months(chrono::Months::new(months))),
             }
             .ok_or(Rich::custom(span, "not a valid date"))
         })
@@ -80,20 +79,19 @@ fn periods_ahead<'a>(
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("ahead"))
         .try_map(|(length, period), span| {
+            let length = length
+                .parse::<u32>()
+                .map_err(|error| Rich::custom(span, error))?;
             match period {
-                Period::Day => {
-                    today().checked_add_days(chrono::Days::new(length.parse::<u64>().unwrap()))
-                }
-                Period::Week => {
-                    today().checked_add_days(chrono::Days::new(length.parse::<u64>().unwrap() * 7))
-                }
-                Period::Month => {
-                    today().checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap()))
-                }
-                Period::Quarter => today()
-                    .checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap() * 3)),
-                Period::Year => today()
-                    .checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap() * 12)),
+                Period::Day => today().checked_add_days(chrono::Days::new(u64::from(length))),
+                Period::Week => today().checked_add_days(chrono::Days::new(u64::from(length) * 7)),
+                Period::Month => today().checked_add_months(chrono::Months::new(length)),
+                Period::Quarter => length
+                    .checked_mul(3)
+                    .and_then(|months| today().checked_add_months(chrono::Months::new(months))),
+                Period::Year => length
+                    .checked_mul(12)
+                    .and_then(|months| today().checked_add_months(chrono::Months::new(months))),
             }
             .ok_or(Rich::custom(span, "not a valid date"))
         })
@@ -108,20 +106,19 @@ fn n_periods<'a>(
         .then_ignore(whitespace().repeated().at_least(1))
         .then(period())
         .try_map(|(length, period), span| {
+            let length = length
+                .parse::<u32>()
+                .map_err(|error| Rich::custom(span, error))?;
             match period {
-                Period::Day => {
-                    today().checked_add_days(chrono::Days::new(length.parse::<u64>().unwrap()))
-                }
-                Period::Week => {
-                    today().checked_add_days(chrono::Days::new(length.parse::<u64>().unwrap() * 7))
-                }
-                Period::Month => {
-                    today().checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap()))
-                }
-                Period::Quarter => today()
-                    .checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap() * 3)),
-                Period::Year => today()
-                    .checked_add_months(chrono::Months::new(length.parse::<u32>().unwrap() * 12)),
+                Period::Day => today().checked_add_days(chrono::Days::new(u64::from(length))),
+                Period::Week => today().checked_add_days(chrono::Days::new(u64::from(length) * 7)),
+                Period::Month => today().checked_add_months(chrono::Months::new(length)),
+                Period::Quarter => length
+                    .checked_mul(3)
+                    .and_then(|months| today().checked_add_months(chrono::Months::new(months))),
+                Period::Year => length
+                    .checked_mul(12)
+                    .and_then(|months| today().checked_add_months(chrono::Months::new(months))),
             }
             .ok_or(Rich::custom(span, "not a valid date"))
         })
@@ -364,7 +361,7 @@ fn today() -> chrono::NaiveDate {
 // oct or october: October 1st in current year
 fn month_name<'a>(
 ) -> impl Parser<'a, &'a str, chrono::NaiveDate, extra::Full<Rich<'a, char>, State, ()>> {
-    let start_of_month = |m: u32| today().with_month(m).unwrap().with_day(1).unwrap();
+    let start_of_month = |m: u32| today().with_day(1).unwrap().with_month(m).unwrap();
     choice([
         just("january").to(start_of_month(1)),
         just("jan").to(start_of_month(1)),
@@ -497,7 +494,7 @@ mod tests {
         let result = date().then_ignore(end()).parse("10/1").into_result();
         assert_eq!(
             result,
-            Ok(today().with_month(10).unwrap().with_day(1).unwrap())
+            Ok(today().with_day(1).unwrap().with_month(10).unwrap())
         );
     }
 
@@ -512,7 +509,7 @@ mod tests {
         let result = date().then_ignore(end()).parse("october").into_result();
         assert_eq!(
             result,
-            Ok(today().with_month(10).unwrap().with_day(1).unwrap())
+            Ok(today().with_day(1).unwrap().with_month(10).unwrap())
         );
     }
 
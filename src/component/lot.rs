@@ -0,0 +1,120 @@
+use chumsky::prelude::*;
+
+use crate::component::amount::amount;
+use crate::component::date::simple::date;
+use crate::component::price::Price;
+use crate::component::whitespace::whitespace;
+use crate::state::State;
+use crate::text::Text;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lot<T = String> {
+    // unit cost in {}, or total cost in {{}}
+    pub cost: Option<Price<T>>,
+    pub date: Option<chrono::NaiveDate>,
+    pub note: Option<T>,
+}
+
+impl<'a, T: Text<'a>> Lot<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Lot {
+        Lot {
+            cost: self.cost.map(Price::into_owned),
+            date: self.date,
+            note: self.note.map(Text::into_string),
+        }
+    }
+}
+
+// lot annotations of an amount: {cost} or {{total cost}}, [date] and (note), in this order
+pub fn lot<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Lot<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    let lot_cost = |open: &'static str, close: &'static str| {
+        just(open)
+            .ignore_then(whitespace().repeated())
+            .ignore_then(just("=").then(whitespace().repeated()).or_not())
+            .ignore_then(amount())
+            .then_ignore(whitespace().repeated())
+            .then_ignore(just(close))
+    };
+    let cost = lot_cost("{{", "}}")
+        .map(Price::Total)
+        .or(lot_cost("{", "}").map(Price::Unit));
+    let lot_date = date().delimited_by(
+        just("[").then(whitespace().repeated()),
+        whitespace().repeated().then(just("]")),
+    );
+    let note = any()
+        .and_is(text::newline().not())
+        .and_is(just(")").not())
+        .repeated()
+        .at_least(1)
+        .to_slice()
+        .delimited_by(just("("), just(")"));
+
+    cost.or_not()
+        .then(whitespace().repeated().ignore_then(lot_date).or_not())
+        .then(whitespace().repeated().ignore_then(note).or_not())
+        .try_map(|((cost, date), note), span| {
+            if cost.is_none() && date.is_none() && note.is_none() {
+                return Err(Rich::custom(span, "expected lot annotation"));
+            }
+            Ok(Lot {
+                cost,
+                date,
+                note: note.map(|note: &str| T::from_slice(note.trim())),
+            })
+        })
+        // keeps the types of parsers built on top of it small enough to compile
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::{amount::Amount, commodity::Commodity, quantity::Quantity};
+
+    use super::*;
+
+    #[test]
+    fn unit_cost() {
+        let result = lot().then_ignore(end()).parse("{ $50 }").into_result();
+        assert_eq!(
+            result,
+            Ok(Lot {
+                cost: Some(Price::Unit(Amount {
+                    quantity: Quantity::from_u64(50),
+                    commodity: Commodity::from_str("$"),
+                    ..Amount::default()
+                })),
+                date: None,
+                note: None,
+            })
+        );
+    }
+
+    #[test]
+    fn full() {
+        let result = lot()
+            .then_ignore(end())
+            .parse("{{=$500}} [2024-01-02] (first buy)")
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(Lot {
+                cost: Some(Price::Total(Amount {
+                    quantity: Quantity::from_u64(500),
+                    commodity: Commodity::from_str("$"),
+                    ..Amount::default()
+                })),
+                date: chrono::NaiveDate::from_ymd_opt(2024, 1, 2),
+                note: Some(String::from("first buy")),
+            })
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let result = lot::<String>().then_ignore(end()).parse("").into_result();
+        assert!(result.is_err());
+    }
+}
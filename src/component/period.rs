@@ -97,9 +97,10 @@ fn year_quarter<'a>() -> impl Parser<
                 .unwrapped(),
         )
         .map(|(year, q)| {
+            let begin = chrono::NaiveDate::from_ymd_opt(year, (q - 1) * 3 + 1, 1);
             (
-                chrono::NaiveDate::from_ymd_opt(year, (q - 1) * 3 + 1, 1),
-                chrono::NaiveDate::from_ymd_opt(year, (q - 1) * 3 + 4, 1),
+                begin,
+                begin.and_then(|begin| begin.checked_add_months(chrono::Months::new(3))),
             )
         })
 }
@@ -117,9 +118,10 @@ fn quarter<'a>() -> impl Parser<
         .from_str::<u32>()
         .unwrapped()
         .map(|q| {
+            let begin = chrono::NaiveDate::from_ymd_opt(today().year(), (q - 1) * 3 + 1, 1);
             (
-                today().with_month((q - 1) * 3 + 1).unwrap().with_day(1),
-                today().with_month((q - 1) * 3 + 4).unwrap().with_day(1),
+                begin,
+                begin.and_then(|begin| begin.checked_add_months(chrono::Months::new(3))),
             )
         })
 }
@@ -221,9 +223,10 @@ fn year_month<'a>() -> impl Parser<
                 .unwrapped(),
         )
         .map(|(year, month)| {
+            let begin = chrono::NaiveDate::from_ymd_opt(year, month, 1);
             (
-                chrono::NaiveDate::from_ymd_opt(year, month, 1),
-                chrono::NaiveDate::from_ymd_opt(year, month + 1, 1),
+                begin,
+                begin.and_then(|begin| begin.checked_add_months(chrono::Months::new(1))),
             )
         })
 }
@@ -369,6 +372,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn december() {
+        let result = period().then_ignore(end()).parse("2009-12").into_result();
+        assert_eq!(
+            result,
+            Ok(Period {
+                interval: None,
+                begin: Some(chrono::NaiveDate::from_ymd_opt(2009, 12, 1).unwrap()),
+                end: Some(chrono::NaiveDate::from_ymd_opt(2010, 1, 1).unwrap()),
+            })
+        );
+    }
+
     #[test]
     fn day() {
         let result = period().then_ignore(end()).parse("2009/1/1").into_result();
@@ -389,8 +405,31 @@ mod tests {
             result,
             Ok(Period {
                 interval: None,
-                begin: today().with_month(7).unwrap().with_day(1),
-                end: today().with_month(10).unwrap().with_day(1),
+                begin: chrono::NaiveDate::from_ymd_opt(today().year(), 7, 1),
+                end: chrono::NaiveDate::from_ymd_opt(today().year(), 10, 1),
+            })
+        );
+    }
+
+    #[test]
+    fn last_quarter() {
+        let result = period().then_ignore(end()).parse("2009q4").into_result();
+        assert_eq!(
+            result,
+            Ok(Period {
+                interval: None,
+                begin: chrono::NaiveDate::from_ymd_opt(2009, 10, 1),
+                end: chrono::NaiveDate::from_ymd_opt(2010, 1, 1),
+            })
+        );
+
+        let result = period().then_ignore(end()).parse("q4").into_result();
+        assert_eq!(
+            result,
+            Ok(Period {
+                interval: None,
+                begin: chrono::NaiveDate::from_ymd_opt(today().year(), 10, 1),
+                end: chrono::NaiveDate::from_ymd_opt(today().year() + 1, 1, 1),
             })
         );
     }
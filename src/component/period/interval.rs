@@ -1,3 +1,4 @@
+use chrono::Datelike;
 use chumsky::prelude::*;
 
 use crate::{component::whitespace::whitespace, state::State};
@@ -18,6 +19,41 @@ pub enum Interval {
     Weekday(chrono::Weekday),
 }
 
+impl Interval {
+    // first day of the period containing the date: periods start on mondays, on the first day of
+    // a month, quarter or year, or on the given week day
+    #[must_use]
+    pub fn start(&self, date: chrono::NaiveDate) -> chrono::NaiveDate {
+        let first_of_month =
+            |month: u32| chrono::NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date);
+        match self {
+            Interval::NthDay(_) => date,
+            Interval::NthWeek(_) => date.week(chrono::Weekday::Mon).first_day(),
+            Interval::NthMonth(_) => first_of_month(date.month()),
+            Interval::NthQuarter(_) => first_of_month(date.month0() / 3 * 3 + 1),
+            Interval::NthYear(_) => first_of_month(1),
+            Interval::Weekday(weekday) => date.week(*weekday).first_day(),
+        }
+    }
+
+    // first day of the period following the one starting on the given day
+    #[must_use]
+    pub fn next(&self, start: chrono::NaiveDate) -> chrono::NaiveDate {
+        let months = |n: u32| start.checked_add_months(chrono::Months::new(n));
+        let days = |n: u32| start.checked_add_days(chrono::Days::new(n.into()));
+        match self {
+            Interval::NthDay(n) => days(*n),
+            Interval::NthWeek(n) => days(n.saturating_mul(7)),
+            Interval::NthMonth(n) => months(*n),
+            Interval::NthQuarter(n) => months(n.saturating_mul(3)),
+            Interval::NthYear(n) => months(n.saturating_mul(12)),
+            Interval::Weekday(_) => days(7),
+        }
+        .filter(|next| *next > start)
+        .unwrap_or(chrono::NaiveDate::MAX)
+    }
+}
+
 // TODO:
 // every Nth day [of month] (31st day will be adjusted to each month's last day)
 // every Nth WEEKDAYNAME [of month]
@@ -36,7 +72,7 @@ pub fn interval<'a>() -> impl Parser<'a, &'a str, Interval, extra::Full<Rich<'a,
         just("monthly").to(Interval::NthMonth(1)),
         just("bimonthly").to(Interval::NthMonth(2)),
         just("quarterly").to(Interval::NthQuarter(1)),
-        just("yearly").to(Interval::NthQuarter(1)),
+        just("yearly").to(Interval::NthYear(1)),
     ]);
 
     word.or(every()).or(day_of_week())
@@ -141,6 +177,9 @@ fn day_of_week<'a>() -> impl Parser<'a, &'a str, Interval, extra::Full<Rich<'a,
 }
 
 fn every<'a>() -> impl Parser<'a, &'a str, Interval, extra::Full<Rich<'a, char>, State, ()>> {
+    let number = text::int(10)
+        .from_str::<u32>()
+        .try_map(|number, span| number.map_err(|error| Rich::custom(span, error)));
     let every = just("every")
         .then(whitespace().repeated().at_least(1))
         .ignore_then(choice([
@@ -152,31 +191,31 @@ fn every<'a>() -> impl Parser<'a, &'a str, Interval, extra::Full<Rich<'a, char>,
         ]));
     let every_n_days = just("every")
         .then(whitespace().repeated().at_least(1))
-        .ignore_then(text::int(10).from_str::<u32>().unwrapped())
+        .ignore_then(number)
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("days"))
         .map(Interval::NthDay);
     let every_n_weeks = just("every")
         .then(whitespace().repeated().at_least(1))
-        .ignore_then(text::int(10).from_str::<u32>().unwrapped())
+        .ignore_then(number)
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("weeks"))
         .map(Interval::NthWeek);
     let every_n_months = just("every")
         .then(whitespace().repeated().at_least(1))
-        .ignore_then(text::int(10).from_str::<u32>().unwrapped())
+        .ignore_then(number)
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("months"))
         .map(Interval::NthMonth);
     let every_n_quarterd = just("every")
         .then(whitespace().repeated().at_least(1))
-        .ignore_then(text::int(10).from_str::<u32>().unwrapped())
+        .ignore_then(number)
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("quarters"))
         .map(Interval::NthQuarter);
     let every_n_years = just("every")
         .then(whitespace().repeated().at_least(1))
-        .ignore_then(text::int(10).from_str::<u32>().unwrapped())
+        .ignore_then(number)
         .then_ignore(whitespace().repeated().at_least(1))
         .then_ignore(just("years"))
         .map(Interval::NthYear);
@@ -282,6 +321,36 @@ mod tests {
         assert_eq!(result, Ok(Interval::NthYear(10)));
     }
 
+    #[test]
+    fn yearly() {
+        let result = interval().then_ignore(end()).parse("yearly").into_result();
+        assert_eq!(result, Ok(Interval::NthYear(1)));
+    }
+
+    #[test]
+    fn start_and_next() {
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        for (interval, start, next) in [
+            (Interval::NthDay(2), date(5, 15), date(5, 17)),
+            (Interval::NthWeek(1), date(5, 13), date(5, 20)),
+            (Interval::NthMonth(1), date(5, 1), date(6, 1)),
+            (Interval::NthQuarter(1), date(4, 1), date(7, 1)),
+            (
+                Interval::NthYear(1),
+                date(1, 1),
+                date(1, 1).with_year(2025).unwrap(),
+            ),
+            (
+                Interval::Weekday(chrono::Weekday::Thu),
+                date(5, 9),
+                date(5, 16),
+            ),
+        ] {
+            assert_eq!(interval.start(date(5, 15)), start, "{interval:?}");
+            assert_eq!(interval.next(start), next, "{interval:?}");
+        }
+    }
+
     #[test]
     fn every_weekday() {
         let result = interval()
@@ -3,14 +3,26 @@ use chumsky::prelude::*;
 use crate::component::amount::{amount, Amount};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum Price {
-    Unit(Amount),
-    Total(Amount),
+pub enum Price<T = String> {
+    Unit(Amount<T>),
+    Total(Amount<T>),
 }
 
-pub fn price<'a>() -> impl Parser<'a, &'a str, Price, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Price<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Price {
+        match self {
+            Price::Unit(amount) => Price::Unit(amount.into_owned()),
+            Price::Total(amount) => Price::Total(amount.into_owned()),
+        }
+    }
+}
+
+pub fn price<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Price<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("@")
         .repeated()
         .at_least(1)
@@ -44,7 +56,8 @@ mod tests {
                 quantity: crate::component::quantity::Quantity {
                     mantissa: 135,
                     places: 2,
-                }
+                },
+                ..Amount::default()
             }))
         );
     }
@@ -60,7 +73,8 @@ mod tests {
                 quantity: crate::component::quantity::Quantity {
                     mantissa: 135,
                     places: 2,
-                }
+                },
+                ..Amount::default()
             }))
         );
     }
@@ -1,4 +1,5 @@
 use chumsky::prelude::*;
+use rust_decimal::Decimal;
 
 use crate::state::State;
 
@@ -15,38 +16,99 @@ impl Quantity {
             places: 0,
         }
     }
+
+    // quantities with more than 28 decimal places do not fit, and are rounded
+    #[must_use]
+    pub fn to_decimal(&self) -> Decimal {
+        let mut mantissa = i128::from(self.mantissa);
+        let mut places = self.places;
+        while places > u64::from(Decimal::MAX_SCALE) {
+            mantissa /= 10;
+            places -= 1;
+        }
+        Decimal::from_i128_with_scale(mantissa, u32::try_from(places).unwrap_or_default())
+    }
+
+    // absolute value of the decimal, dropping decimal places if the mantissa is too large
+    #[must_use]
+    pub fn from_decimal(value: Decimal) -> Self {
+        Self::checked_from_decimal(value).unwrap_or(Self::from_u64(u64::MAX))
+    }
+
+    // same as from_decimal, or None if the integer part of the decimal does not fit
+    #[must_use]
+    pub fn checked_from_decimal(value: Decimal) -> Option<Self> {
+        let mut value = value.abs();
+        loop {
+            match u64::try_from(value.mantissa()) {
+                Ok(mantissa) => {
+                    return Some(Self {
+                        mantissa,
+                        places: value.scale().into(),
+                    })
+                }
+                Err(_) if value.scale() > 0 => value = value.round_dp(value.scale() - 1),
+                Err(_) => return None,
+            }
+        }
+    }
 }
 
 pub fn quantity<'a>() -> impl Parser<'a, &'a str, Quantity, extra::Full<Rich<'a, char>, State, ()>>
 {
-    let fraction = fraction(',').or(fraction('.')).map(|fraction| Quantity {
-        mantissa: fraction.parse().unwrap(),
-        places: fraction.len().try_into().unwrap(),
-    });
-    let thousands = thousands(',').or(thousands('.')).map(|(first, rest)| {
-        if rest.len() == 1 {
+    let fraction = fraction(',')
+        .or(fraction('.'))
+        .try_map(|fraction, span| to_quantity(&fraction, fraction.len(), span));
+    let thousands = thousands(',')
+        .or(thousands('.'))
+        .try_map(|(first, rest), span| {
             // i.e. "1,234" - it could be either a decimal, or an integer with a thousands
             // separator. we treat it as a decimal.
-            Quantity {
-                mantissa: (first + rest.join("").as_ref()).parse().unwrap(),
-                places: 3,
-            }
-        } else {
-            Quantity {
-                mantissa: (first + rest.join("").as_ref()).parse().unwrap(),
-                places: 0,
-            }
-        }
-    });
-    let digits = digits().map(|integer| Quantity {
-        mantissa: integer.parse().unwrap(),
-        places: 0,
-    });
+            let places = if rest.len() == 1 { 3 } else { 0 };
+            to_quantity(&(first + rest.join("").as_ref()), places, span)
+        });
+    let digits = digits().try_map(|integer, span| to_quantity(&integer, 0, span));
     thousands_and_decimals()
         .or(thousands)
         .or(decimal())
         .or(fraction)
         .or(digits)
+        .try_map_with(|quantity, e| {
+            let state: &mut State = e.state();
+            match state.decimal_mark {
+                Some(mark) => with_decimal_mark(e.slice(), mark, e.span()),
+                None => Ok(quantity),
+            }
+        })
+}
+
+// number read with the mark of a decimal-mark directive, so that "1,234" is not ambiguous. the
+// other mark can only separate digit groups.
+fn with_decimal_mark<'a>(
+    text: &str,
+    mark: char,
+    span: SimpleSpan,
+) -> Result<Quantity, Rich<'a, char>> {
+    let digits = text
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    match text.rfind(|c: char| !c.is_ascii_digit()) {
+        Some(index) if text[index..].starts_with(mark) => {
+            to_quantity(&digits, text.len() - index - 1, span)
+        }
+        Some(_)
+            if text.contains(mark)
+                || text.starts_with(|c: char| !c.is_ascii_digit())
+                || text.ends_with(|c: char| !c.is_ascii_digit()) =>
+        {
+            Err(Rich::custom(
+                span,
+                format!("{text} does not have {mark} as its decimal mark."),
+            ))
+        }
+        _ => to_quantity(&digits, 0, span),
+    }
 }
 
 fn digit<'a>() -> impl Parser<'a, &'a str, char, extra::Full<Rich<'a, char>, State, ()>> {
@@ -86,11 +148,12 @@ fn thousands_and_decimals<'a>(
     thousands(',')
         .then(fraction('.'))
         .or(thousands('.').then(fraction(',')))
-        .map(|(thousands, decimals)| Quantity {
-            mantissa: (thousands.0 + thousands.1.join("").as_ref() + decimals.as_ref())
-                .parse()
-                .unwrap(),
-            places: decimals.len().try_into().unwrap(),
+        .try_map(|(thousands, decimals), span| {
+            to_quantity(
+                &(thousands.0 + thousands.1.join("").as_ref() + decimals.as_ref()),
+                decimals.len(),
+                span,
+            )
         })
 }
 
@@ -98,9 +161,9 @@ fn thousands<'a>(
     mark: char,
 ) -> impl Parser<'a, &'a str, (String, Vec<String>), extra::Full<Rich<'a, char>, State, ()>> {
     one_to_three_digits().then(
-        three_digits()
-            .separated_by(just(mark))
-            .allow_leading()
+        just(mark)
+            .ignore_then(three_digits())
+            .repeated()
             .at_least(1)
             .collect(),
     )
@@ -110,12 +173,26 @@ fn decimal<'a>() -> impl Parser<'a, &'a str, Quantity, extra::Full<Rich<'a, char
     let decimal = |mark: char| digits().then(fraction(mark));
     decimal(',')
         .or(decimal('.'))
-        .map(|(integer, decimals)| Quantity {
-            mantissa: (integer + decimals.as_ref()).parse().unwrap(),
-            places: decimals.len().try_into().unwrap(),
+        .try_map(|(integer, decimals), span| {
+            to_quantity(&(integer + decimals.as_ref()), decimals.len(), span)
         })
 }
 
+// digits of a number without separators, the last places of them after the decimal mark
+fn to_quantity<'a>(
+    digits: &str,
+    places: usize,
+    span: SimpleSpan,
+) -> Result<Quantity, Rich<'a, char>> {
+    if digits.is_empty() {
+        return Err(Rich::custom(span, "expected digits."));
+    }
+    match (digits.parse(), u64::try_from(places)) {
+        (Ok(mantissa), Ok(places)) => Ok(Quantity { mantissa, places }),
+        _ => Err(Rich::custom(span, format!("{digits} is too large."))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,6 +212,21 @@ mod tests {
             );
         }
 
+        #[test]
+        fn integer_long() {
+            for (input, mantissa) in [("100000", 100_000), ("1234567", 1_234_567)] {
+                let result = quantity().then_ignore(end()).parse(input).into_result();
+                assert_eq!(
+                    result,
+                    Ok(Quantity {
+                        mantissa,
+                        places: 0,
+                    }),
+                    "{input}"
+                );
+            }
+        }
+
         #[test]
         fn integer_trailing() {
             let result1 = quantity().then_ignore(end()).parse("123.").into_result();
@@ -265,5 +357,46 @@ mod tests {
                 })
             );
         }
+
+        #[test]
+        fn too_large() {
+            for input in ["123456789012345678901", "1.23456789012345678901"] {
+                let result = quantity().then_ignore(end()).parse(input).into_result();
+                assert!(result.is_err(), "{input}");
+            }
+        }
+
+        #[test]
+        fn mark_only() {
+            let result = quantity().then_ignore(end()).parse(".").into_result();
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn declared_decimal_mark() {
+            let parse = |input: &str| {
+                let mut state = State {
+                    decimal_mark: Some(','),
+                    ..State::default()
+                };
+                quantity()
+                    .then_ignore(end())
+                    .parse_with_state(input, &mut state)
+                    .into_result()
+                    .ok()
+            };
+            for (input, mantissa, places) in [
+                ("1,234", 1_234, 3),
+                ("1.234", 1_234, 0),
+                ("1.234.567,5", 12_345_675, 1),
+                ("1,5", 15, 1),
+                ("12", 12, 0),
+            ] {
+                assert_eq!(parse(input), Some(Quantity { mantissa, places }), "{input}");
+            }
+            for input in ["1,234.5", "1.", ".5"] {
+                assert_eq!(parse(input), None, "{input}");
+            }
+        }
     }
 }
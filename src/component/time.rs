@@ -4,9 +4,17 @@ use crate::state::State;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Time {
-    hours: u16,
-    minutes: u16,
-    seconds: u16,
+    pub hours: u16,
+    pub minutes: u16,
+    pub seconds: u16,
+}
+
+impl Time {
+    #[must_use]
+    pub fn to_naive_time(&self) -> chrono::NaiveTime {
+        chrono::NaiveTime::from_hms_opt(self.hours.into(), self.minutes.into(), self.seconds.into())
+            .unwrap_or_default()
+    }
 }
 
 pub fn time<'a>() -> impl Parser<'a, &'a str, Time, extra::Full<Rich<'a, char>, State, ()>> {
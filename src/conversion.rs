@@ -0,0 +1,224 @@
+use crate::component::amount::Amount;
+use crate::component::commodity::Commodity;
+use crate::component::lot::Lot;
+use crate::component::price::Price;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::price_db::PriceDb;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Conversion {
+    // amounts at their cost basis, from the lot cost or otherwise the transaction price
+    Cost,
+    // market value in the commodity of the latest price of each commodity. without a date, the
+    // value at the end of each report column.
+    Value(Option<chrono::NaiveDate>),
+    // market value in the given commodity
+    Exchange(Commodity, Option<chrono::NaiveDate>),
+}
+
+impl Conversion {
+    // market value of the amounts on the conversion's date, otherwise on the given date. costs
+    // are converted per posting, so they are kept as they are.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn value(
+        &self,
+        amount: &MixedAmount,
+        prices: &PriceDb,
+        date: chrono::NaiveDate,
+    ) -> Result<MixedAmount, Overflow> {
+        match self {
+            Conversion::Cost => Ok(amount.clone()),
+            Conversion::Value(fixed) => value_mixed(amount, prices, None, fixed.unwrap_or(date)),
+            Conversion::Exchange(commodity, fixed) => {
+                value_mixed(amount, prices, Some(commodity), fixed.unwrap_or(date))
+            }
+        }
+    }
+}
+
+// amount converted to the commodity of its lot cost, otherwise of its unit or total price. this
+// is the cost basis, used for cost reports.
+#[allow(clippy::missing_errors_doc)]
+pub fn cost(amount: &Amount, lot: Option<&Lot>, price: Option<&Price>) -> Result<Amount, Overflow> {
+    at_price(amount, lot.and_then(|lot| lot.cost.as_ref()).or(price))
+}
+
+// amount that the posting adds to its transaction's balance: at the transaction price, otherwise
+// at the lot cost. selling a lot above its cost balances at the sale price.
+pub(crate) fn balancing_cost(
+    amount: &Amount,
+    lot: Option<&Lot>,
+    price: Option<&Price>,
+) -> Result<Amount, Overflow> {
+    at_price(amount, price.or(lot.and_then(|lot| lot.cost.as_ref())))
+}
+
+fn at_price(amount: &Amount, price: Option<&Price>) -> Result<Amount, Overflow> {
+    match price {
+        None => Ok(amount.clone()),
+        Some(Price::Unit(price)) => amount
+            .to_decimal()
+            .checked_mul(price.to_decimal())
+            .and_then(|total| {
+                Amount::checked_from_decimal(total, price.commodity.clone(), price.style.clone())
+            })
+            .ok_or(Overflow),
+        Some(Price::Total(price)) => {
+            let total = price.to_decimal().abs();
+            Ok(Amount::from_decimal(
+                if amount.is_negative { -total } else { total },
+                price.commodity.clone(),
+                price.style.clone(),
+            ))
+        }
+    }
+}
+
+// amount converted at the market price on the date, to the given commodity or otherwise to the
+// commodity of its latest price. amounts without a known price are kept as they are.
+#[must_use]
+pub fn value(
+    amount: &Amount,
+    prices: &PriceDb,
+    commodity: Option<&Commodity>,
+    date: chrono::NaiveDate,
+) -> Amount {
+    let latest;
+    let to = match commodity {
+        Some(commodity) => commodity,
+        None => match prices.latest_commodity(&amount.commodity, date) {
+            Some(commodity) => {
+                latest = commodity;
+                &latest
+            }
+            None => return amount.clone(),
+        },
+    };
+    prices
+        .value(amount, to, date)
+        .unwrap_or_else(|| amount.clone())
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn value_mixed(
+    amount: &MixedAmount,
+    prices: &PriceDb,
+    commodity: Option<&Commodity>,
+    date: chrono::NaiveDate,
+) -> Result<MixedAmount, Overflow> {
+    let mut valued = MixedAmount::default();
+    for amount in amount.amounts() {
+        valued.add(&value(amount, prices, commodity, date))?;
+    }
+    Ok(valued)
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::prelude::*;
+
+    use crate::component::amount::amount;
+    use crate::component::lot::lot;
+    use crate::component::price::price;
+    use crate::state::State;
+
+    use super::*;
+
+    fn parse_amount(s: &str) -> Amount {
+        amount()
+            .parse_with_state(s, &mut State::default())
+            .into_result()
+            .unwrap()
+    }
+
+    fn date(month: u32, day: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn sale_of_lot() {
+        let amount = parse_amount("-4 AAPL");
+        let lot = lot()
+            .parse_with_state("{$50}", &mut State::default())
+            .into_result()
+            .unwrap();
+        let price = price()
+            .parse_with_state("@ $60", &mut State::default())
+            .into_result()
+            .unwrap();
+        // the lot cost is the cost basis, the sale balances at the price it was sold for
+        assert_eq!(
+            cost(&amount, Some(&lot), Some(&price)).unwrap().to_string(),
+            "$-200"
+        );
+        assert_eq!(
+            balancing_cost(&amount, Some(&lot), Some(&price))
+                .unwrap()
+                .to_string(),
+            "$-240"
+        );
+        assert_eq!(
+            balancing_cost(&amount, Some(&lot), None)
+                .unwrap()
+                .to_string(),
+            "$-200"
+        );
+        assert_eq!(
+            cost(&amount, None, Some(&price)).unwrap().to_string(),
+            "$-240"
+        );
+        assert_eq!(cost(&amount, None, None).unwrap().to_string(), "-4 AAPL");
+    }
+
+    #[test]
+    fn overflow() {
+        let amount = parse_amount("10000000000 X");
+        for text in ["@ 10000000000000000000 Y", "@ 10000000000 Y"] {
+            let price = price()
+                .parse_with_state(text, &mut State::default())
+                .into_result()
+                .unwrap();
+            assert_eq!(cost(&amount, None, Some(&price)), Err(Overflow));
+        }
+    }
+
+    #[test]
+    fn market_value() {
+        let directives = crate::parse(
+            "P 2024-01-01 AAPL $100.00
+P 2024-02-01 AAPL $120.00
+P 2024-01-01 EUR $1.25
+",
+        )
+        .unwrap();
+        let prices = PriceDb::from_directives(&directives);
+        let mut amount = MixedAmount::from_amount(&parse_amount("2 AAPL"));
+        amount.add(&parse_amount("10 EUR")).unwrap();
+        amount.add(&parse_amount("3 XYZ")).unwrap();
+        assert_eq!(
+            Conversion::Value(None)
+                .value(&amount, &prices, date(1, 15))
+                .unwrap()
+                .to_string(),
+            "$212.50, 3 XYZ"
+        );
+        assert_eq!(
+            Conversion::Value(Some(date(2, 1)))
+                .value(&amount, &prices, date(1, 15))
+                .unwrap()
+                .to_string(),
+            "$252.50, 3 XYZ"
+        );
+        assert_eq!(
+            Conversion::Exchange(Commodity::from_str("$"), None)
+                .value(
+                    &MixedAmount::from_amount(&parse_amount("10 EUR")),
+                    &prices,
+                    date(1, 15)
+                )
+                .unwrap()
+                .to_string(),
+            "$12.50"
+        );
+    }
+}
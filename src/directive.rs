@@ -1,51 +1,109 @@
-mod account;
-mod auto_postings;
-mod commodity;
-mod decimal_mark;
-mod include;
-mod payee;
-mod price;
-mod tag;
-mod transaction;
-mod year;
+pub mod account;
+pub mod alias;
+pub mod apply_account;
+pub mod auto_postings;
+pub mod commodity;
+pub mod decimal_mark;
+pub mod default_account;
+pub mod default_commodity;
+pub mod include;
+pub mod payee;
+pub mod price;
+pub mod tag;
+pub mod transaction;
+pub mod year;
 
 use chumsky::prelude::*;
 
 use crate::component::comment::{block, inline, line};
 use crate::component::whitespace::whitespace;
 use crate::directive::account::{account, Account};
+use crate::directive::alias::{alias, end_aliases, Alias};
+use crate::directive::apply_account::{apply_account, end_apply_account, ApplyAccount};
 use crate::directive::auto_postings::{auto_postings, AutoPostings};
 use crate::directive::commodity::{commodity, Commodity};
 use crate::directive::decimal_mark::{decimal_mark, DecimalMark};
+use crate::directive::default_account::{default_account, DefaultAccount};
+use crate::directive::default_commodity::{default_commodity, DefaultCommodity};
 use crate::directive::include::{include, Include};
 use crate::directive::payee::{payee, Payee};
 use crate::directive::price::{price, Price};
 use crate::directive::tag::{tag, Tag};
 use crate::directive::year::{year, Year};
 use crate::state::State;
+use crate::text::Text;
 
-#[derive(Clone, Debug)]
-pub enum Directive {
-    Account(Account),
-    AutoPostings(AutoPostings),
-    Commodity(Commodity),
+// text is owned by default. aliases, includes and other directives without text of their own are
+// the same either way.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Directive<T = String> {
+    Account(Account<T>),
+    Alias(Alias),
+    ApplyAccount(ApplyAccount<T>),
+    AutoPostings(AutoPostings<T>),
+    Commodity(Commodity<T>),
     DecimalMark(DecimalMark),
+    DefaultAccount(DefaultAccount<T>),
+    DefaultCommodity(DefaultCommodity<T>),
+    EndAliases,
+    EndApplyAccount,
     Include(Include),
-    Payee(Payee),
-    Price(Price),
-    Tag(Tag),
-    Transaction(transaction::Simple),
-    PeriodicTransaction(transaction::Periodic),
+    Payee(Payee<T>),
+    Price(Price<T>),
+    Tag(Tag<T>),
+    Transaction(transaction::Simple<T>),
+    PeriodicTransaction(transaction::Periodic<T>),
     Year(Year),
 }
 
-pub fn directive<'a>() -> impl Parser<'a, &'a str, Directive, extra::Full<Rich<'a, char>, State, ()>>
-{
+impl<'a, T: Text<'a>> Directive<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Directive {
+        match self {
+            Directive::Account(account) => Directive::Account(account.into_owned()),
+            Directive::Alias(alias) => Directive::Alias(alias),
+            Directive::ApplyAccount(apply_account) => {
+                Directive::ApplyAccount(apply_account.into_owned())
+            }
+            Directive::AutoPostings(auto_postings) => {
+                Directive::AutoPostings(auto_postings.into_owned())
+            }
+            Directive::Commodity(commodity) => Directive::Commodity(commodity.into_owned()),
+            Directive::DecimalMark(decimal_mark) => Directive::DecimalMark(decimal_mark),
+            Directive::DefaultAccount(default_account) => {
+                Directive::DefaultAccount(default_account.into_owned())
+            }
+            Directive::DefaultCommodity(default_commodity) => {
+                Directive::DefaultCommodity(default_commodity.into_owned())
+            }
+            Directive::EndAliases => Directive::EndAliases,
+            Directive::EndApplyAccount => Directive::EndApplyAccount,
+            Directive::Include(include) => Directive::Include(include),
+            Directive::Payee(payee) => Directive::Payee(payee.into_owned()),
+            Directive::Price(price) => Directive::Price(price.into_owned()),
+            Directive::Tag(tag) => Directive::Tag(tag.into_owned()),
+            Directive::Transaction(transaction) => Directive::Transaction(transaction.into_owned()),
+            Directive::PeriodicTransaction(transaction) => {
+                Directive::PeriodicTransaction(transaction.into_owned())
+            }
+            Directive::Year(year) => Directive::Year(year),
+        }
+    }
+}
+
+pub fn directive<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Directive<T>, extra::Full<Rich<'a, char>, State, ()>> {
     account()
         .map(Directive::Account)
+        .or(alias().map(Directive::Alias))
+        .or(apply_account().map(Directive::ApplyAccount))
         .or(auto_postings().map(Directive::AutoPostings))
         .or(commodity().map(Directive::Commodity))
         .or(decimal_mark().map(Directive::DecimalMark))
+        .or(default_account().map(Directive::DefaultAccount))
+        .or(default_commodity().map(Directive::DefaultCommodity))
+        .or(end_aliases().map(|()| Directive::EndAliases))
+        .or(end_apply_account().map(|()| Directive::EndApplyAccount))
         .or(include().map(Directive::Include))
         .or(payee().map(Directive::Payee))
         .or(price().map(Directive::Price))
@@ -55,13 +113,26 @@ pub fn directive<'a>() -> impl Parser<'a, &'a str, Directive, extra::Full<Rich<'
         .or(year().map(Directive::Year))
 }
 
-pub fn directives<'a>(
-) -> impl Parser<'a, &'a str, Vec<Directive>, extra::Full<Rich<'a, char>, State, ()>> {
+pub fn directives<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Vec<Directive<T>>, extra::Full<Rich<'a, char>, State, ()>> {
+    spanned_directives().map(|directives| {
+        directives
+            .into_iter()
+            .map(|(directive, _)| directive)
+            .collect()
+    })
+}
+
+// directives with the span of their text in the journal
+#[allow(clippy::type_complexity)]
+pub fn spanned_directives<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Vec<(Directive<T>, SimpleSpan)>, extra::Full<Rich<'a, char>, State, ()>>
+{
     directive()
-        .map(Some)
-        .or(inline().map(|_| None))
-        .or(line().map(|_| None))
-        .or(block().map(|_| None))
+        .map_with(|directive, e| Some((directive, e.span())))
+        .or(inline::<T>().map(|_| None))
+        .or(line::<T>().map(|_| None))
+        .or(block::<T>().map(|_| None))
         .or(whitespace().repeated().map(|()| None))
         .separated_by(text::newline())
         .collect::<Vec<_>>()
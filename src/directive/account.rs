@@ -3,21 +3,75 @@ use chumsky::prelude::*;
 use crate::component::account_name::{account_name, AccountName};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line_prefixed;
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Account {
-    pub account_name: AccountName,
+pub struct Account<T = String> {
+    pub account_name: AccountName<T>,
+    // declared with a "type:" tag in the account's comment
+    pub account_type: Option<AccountType>,
 }
 
-pub fn account<'a>() -> impl Parser<'a, &'a str, Account, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Account<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Account {
+        Account {
+            account_name: self.account_name.into_owned(),
+            account_type: self.account_type,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountType {
+    Asset,
+    Liability,
+    Equity,
+    Revenue,
+    Expense,
+    // asset that is cash or cash equivalent
+    Cash,
+    // equity used for commodity conversions
+    Conversion,
+}
+
+impl std::str::FromStr for AccountType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "a" | "asset" | "assets" => Ok(AccountType::Asset),
+            "l" | "liability" | "liabilities" => Ok(AccountType::Liability),
+            "e" | "equity" => Ok(AccountType::Equity),
+            "r" | "revenue" | "revenues" => Ok(AccountType::Revenue),
+            "x" | "expense" | "expenses" => Ok(AccountType::Expense),
+            "c" | "cash" => Ok(AccountType::Cash),
+            "v" | "conversion" => Ok(AccountType::Conversion),
+            _ => Err(format!("unknown account type: {s}")),
+        }
+    }
+}
+
+pub fn account<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Account<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("account")
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(account_name())
-        .then_ignore(
-            end_of_line_prefixed(2), // The two-space requirement for same-line account comments is because ; is allowed in account names.
+        .then(
+            end_of_line_prefixed::<T>(2), // The two-space requirement for same-line account comments is because ; is allowed in account names.
         )
-        .map(|account_name| Account { account_name })
+        .try_map(|(account_name, comment), span| {
+            let account_type = comment
+                .and_then(|comment| comment.tag("type"))
+                .map(|account_type| account_type.parse::<AccountType>())
+                .transpose()
+                .map_err(|error| Rich::custom(span, error))?;
+            Ok(Account {
+                account_name,
+                account_type,
+            })
+        })
 }
 
 #[cfg(test)]
@@ -37,7 +91,8 @@ mod tests {
                     String::from("one"),
                     String::from("two"),
                     String::from("three"),
-                ])
+                ]),
+                account_type: None,
             })
         );
     }
@@ -55,7 +110,8 @@ mod tests {
                     String::from("one"),
                     String::from("two"),
                     String::from("three"),
-                ])
+                ]),
+                account_type: None,
             })
         );
     }
@@ -73,7 +129,8 @@ mod tests {
                     String::from("one"),
                     String::from("two"),
                     String::from("three ; comment"),
-                ])
+                ]),
+                account_type: None,
             })
         );
     }
@@ -91,14 +148,45 @@ mod tests {
                     String::from("one"),
                     String::from("two"),
                     String::from("three"),
-                ])
+                ]),
+                account_type: None,
             })
         );
     }
 
+    #[test]
+    fn with_type() {
+        let result = account::<String>()
+            .then_ignore(end())
+            .parse("account assets:cash  ; cash, type: C")
+            .into_result();
+        assert_eq!(
+            result.map(|account| account.account_type),
+            Ok(Some(AccountType::Cash))
+        );
+
+        let result = account::<String>()
+            .then_ignore(end())
+            .parse("account assets:cash  ; type:Asset\n  ; other comment")
+            .into_result();
+        assert_eq!(
+            result.map(|account| account.account_type),
+            Ok(Some(AccountType::Asset))
+        );
+    }
+
+    #[test]
+    fn unknown_type() {
+        let result = account::<String>()
+            .then_ignore(end())
+            .parse("account assets:cash  ; type: Z")
+            .into_result();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn err() {
-        let result = account()
+        let result = account::<String>()
             .then_ignore(end())
             .parse("acount     one:two:three   ; comment ")
             .into_result();
@@ -0,0 +1,290 @@
+use chumsky::prelude::*;
+
+use crate::component::whitespace::whitespace;
+use crate::state::State;
+use crate::utils::end_of_line;
+
+#[derive(Clone, Debug)]
+pub enum Alias {
+    // alias checking:old = assets:checking
+    Plain {
+        from: String,
+        to: String,
+    },
+    // alias /^expenses:(.*)/ = x:\1
+    Regex {
+        regex: regex::Regex,
+        // replacement with regex crate syntax for groups, i.e. "x:${1}"
+        replacement: String,
+    },
+}
+
+impl PartialEq for Alias {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Alias::Plain { from, to },
+                Alias::Plain {
+                    from: other_from,
+                    to: other_to,
+                },
+            ) => from == other_from && to == other_to,
+            (
+                Alias::Regex { regex, replacement },
+                Alias::Regex {
+                    regex: other_regex,
+                    replacement: other_replacement,
+                },
+            ) => regex.as_str() == other_regex.as_str() && replacement == other_replacement,
+            _ => false,
+        }
+    }
+}
+
+impl Alias {
+    // rewrites full account name, i.e. "assets:checking"
+    #[must_use]
+    pub fn apply(&self, account_name: &str) -> String {
+        match self {
+            Alias::Plain { from, to } => match account_name.strip_prefix(from.as_str()) {
+                Some(rest) if rest.is_empty() || rest.starts_with(':') => format!("{to}{rest}"),
+                _ => account_name.to_string(),
+            },
+            Alias::Regex { regex, replacement } => regex
+                .replace_all(account_name, replacement.as_str())
+                .into_owned(),
+        }
+    }
+}
+
+// parses alias definition the same way as it is written after "alias", i.e. in --alias option
+impl std::str::FromStr for Alias {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        definition()
+            .then_ignore(end())
+            .parse(s)
+            .into_result()
+            .map_err(|errors| {
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+    }
+}
+
+pub fn alias<'a>() -> impl Parser<'a, &'a str, Alias, extra::Full<Rich<'a, char>, State, ()>> {
+    just("alias")
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(definition())
+        .then_ignore(end_of_line::<String>())
+        .map_with(|alias, e| {
+            let state: &mut State = e.state();
+            state.aliases.push(alias.clone());
+            alias
+        })
+}
+
+pub fn end_aliases<'a>() -> impl Parser<'a, &'a str, (), extra::Full<Rich<'a, char>, State, ()>> {
+    just("end")
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(just("aliases"))
+        .ignore_then(end_of_line::<String>())
+        .map_with(|_, e| {
+            let state: &mut State = e.state();
+            state.aliases.clear();
+        })
+}
+
+fn definition<'a>() -> impl Parser<'a, &'a str, Alias, extra::Full<Rich<'a, char>, State, ()>> {
+    let separator = || {
+        whitespace()
+            .repeated()
+            .then(just("="))
+            .then(whitespace().repeated())
+    };
+    let replacement = any()
+        .and_is(text::newline().not())
+        .and_is(just(";").not()) // forbidden, because it indicates comment
+        .repeated()
+        .collect::<String>()
+        .map(|replacement| replacement.trim().to_string());
+
+    let regex = any()
+        .and_is(text::newline().not())
+        .and_is(just("/").then(separator()).not()) // forbidden, because it ends the regex
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+        .delimited_by(just("/"), just("/"))
+        .then_ignore(separator())
+        .then(replacement)
+        .try_map(|(pattern, replacement), span| {
+            regex::RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build()
+                .map(|regex| Alias::Regex {
+                    regex,
+                    replacement: group_references(&replacement),
+                })
+                .map_err(|error| Rich::custom(span, error.to_string()))
+        });
+
+    let plain = just("/") // regex that failed to compile is an error, not a plain alias
+        .not()
+        .ignore_then(
+            any()
+                .and_is(text::newline().not())
+                .and_is(just("=").not()) // forbidden, because it separates old and new names
+                .repeated()
+                .at_least(1)
+                .collect::<String>(),
+        )
+        .then_ignore(separator())
+        .then(replacement)
+        .map(|(from, to)| Alias::Plain {
+            from: from.trim().to_string(),
+            to,
+        });
+
+    regex.or(plain)
+}
+
+// converts \1 style group references to ${1}, escaping everything else
+fn group_references(replacement: &str) -> String {
+    let mut result = String::with_capacity(replacement.len());
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                result.push_str("${");
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    result.push(digit);
+                }
+                result.push('}');
+            }
+            '$' => result.push_str("$$"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::account_name::AccountName;
+    use crate::directive::{directives, Directive};
+
+    use super::*;
+
+    #[test]
+    fn plain() {
+        let result = alias()
+            .then_ignore(end())
+            .parse("alias checking = assets:bank:checking")
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(Alias::Plain {
+                from: String::from("checking"),
+                to: String::from("assets:bank:checking"),
+            })
+        );
+    }
+
+    #[test]
+    fn regex() {
+        let result = alias()
+            .then_ignore(end())
+            .parse("alias /^(.+):bank:(.*)$/ = \\1:\\2  ; with comment")
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(Alias::Regex {
+                regex: regex::Regex::new("^(.+):bank:(.*)$").unwrap(),
+                replacement: String::from("${1}:${2}"),
+            })
+        );
+    }
+
+    #[test]
+    fn regex_invalid() {
+        let result = alias()
+            .then_ignore(end())
+            .parse("alias /(/ = x")
+            .into_result();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn apply_plain() {
+        let alias = "checking = assets:checking".parse::<Alias>().unwrap();
+        assert_eq!(alias.apply("checking"), "assets:checking");
+        assert_eq!(alias.apply("checking:joint"), "assets:checking:joint");
+        assert_eq!(alias.apply("checkings"), "checkings");
+        assert_eq!(alias.apply("old:checking"), "old:checking");
+    }
+
+    #[test]
+    fn apply_regex() {
+        let alias = "/^EXPENSES:(.*)/ = x:\\1 $".parse::<Alias>().unwrap();
+        assert_eq!(alias.apply("expenses:food"), "x:food $");
+        assert_eq!(alias.apply("assets:expenses:food"), "assets:expenses:food");
+    }
+
+    #[test]
+    fn end_clears() {
+        let mut state = State::default();
+        let result = alias()
+            .ignore_then(text::newline())
+            .ignore_then(end_aliases())
+            .then_ignore(end())
+            .parse_with_state("alias a = b\nend aliases", &mut state)
+            .into_result();
+        assert_eq!(result, Ok(()));
+        assert!(state.aliases.is_empty());
+    }
+
+    #[test]
+    fn applied_to_following_postings() {
+        let result = directives()
+            .then_ignore(end())
+            .parse(
+                "2024-01-01
+    checking  $1
+alias /^assets:(.*)$/ = a:\\1
+alias checking = assets:checking
+2024-01-02
+    checking:joint  $1
+end aliases
+2024-01-03
+    checking  $1",
+            )
+            .into_result()
+            .unwrap();
+        let accounts = result
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Transaction(transaction) => {
+                    Some(transaction.postings[0].account_name.clone())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accounts,
+            vec![
+                AccountName::from_strs(&[String::from("checking")]),
+                AccountName::from_strs(&[
+                    String::from("a"),
+                    String::from("checking"),
+                    String::from("joint"),
+                ]),
+                AccountName::from_strs(&[String::from("checking")]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use chumsky::prelude::*;
+
+use crate::component::account_name::{raw_account_name, AccountName};
+use crate::component::whitespace::whitespace;
+use crate::state::State;
+use crate::text::Text;
+use crate::utils::end_of_line;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApplyAccount<T = String> {
+    pub account_name: AccountName<T>,
+}
+
+impl<'a, T: Text<'a>> ApplyAccount<T> {
+    #[must_use]
+    pub fn into_owned(self) -> ApplyAccount {
+        ApplyAccount {
+            account_name: self.account_name.into_owned(),
+        }
+    }
+}
+
+pub fn apply_account<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, ApplyAccount<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    just("apply")
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(just("account"))
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(raw_account_name())
+        .then_ignore(end_of_line::<T>())
+        .map_with(|account_name: AccountName<T>, e| {
+            let state: &mut State = e.state();
+            state
+                .parent_accounts
+                .push(account_name.clone().into_owned());
+            ApplyAccount { account_name }
+        })
+}
+
+pub fn end_apply_account<'a>(
+) -> impl Parser<'a, &'a str, (), extra::Full<Rich<'a, char>, State, ()>> {
+    just("end")
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(just("apply"))
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(just("account"))
+        .ignore_then(end_of_line::<String>())
+        .try_map_with(|_, e| {
+            let span = e.span();
+            let state: &mut State = e.state();
+            state
+                .parent_accounts
+                .pop()
+                .map(|_| ())
+                .ok_or_else(|| Rich::custom(span, "end apply account without apply account."))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::directive::{directives, Directive};
+
+    use super::*;
+
+    #[test]
+    fn with_comment() {
+        let mut state = State::default();
+        let result = apply_account()
+            .then_ignore(end())
+            .parse_with_state("apply account business:acme  ; comment", &mut state)
+            .into_result();
+        let account_name =
+            AccountName::from_strs(&[String::from("business"), String::from("acme")]);
+        assert_eq!(
+            result,
+            Ok(ApplyAccount {
+                account_name: account_name.clone()
+            })
+        );
+        assert_eq!(state.parent_accounts, vec![account_name]);
+    }
+
+    #[test]
+    fn end_without_apply() {
+        let result = end_apply_account()
+            .then_ignore(end())
+            .parse("end apply account")
+            .into_result();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn nested() {
+        let result = directives::<String>()
+            .then_ignore(end())
+            .parse(
+                "apply account business
+apply account acme
+account expenses
+end apply account
+2024-01-01
+    expenses  $1
+end apply account
+account expenses",
+            )
+            .into_result()
+            .unwrap();
+        let accounts = result
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Account(account) => Some(account.account_name.0.join(":")),
+                Directive::Transaction(transaction) => {
+                    Some(transaction.postings[0].account_name.0.join(":"))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            accounts,
+            vec!["business:acme:expenses", "business:expenses", "expenses"]
+        );
+    }
+}
@@ -1,34 +1,54 @@
-mod query;
+pub mod query;
 
 use chumsky::prelude::*;
 
 use crate::component::account_name::{account_name, AccountName};
-use crate::component::amount::{amount, Amount};
+use crate::component::amount::{amount, raw_amount, Amount};
 use crate::component::comment::inline;
 use crate::component::whitespace::whitespace;
 use crate::directive::auto_postings::query::{query, Query};
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct AutoPostings {
-    pub query: Query,
-    pub postings: Vec<AutoPosting>,
+pub struct AutoPostings<T = String> {
+    pub query: Query<T>,
+    pub postings: Vec<AutoPosting<T>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct AutoPosting {
-    pub account_name: AccountName,
+pub struct AutoPosting<T = String> {
+    pub account_name: AccountName<T>,
     pub is_virtual: bool,
-    pub amount: Amount,
+    pub amount: Amount<T>,
     pub is_mul: bool,
 }
 
-pub fn auto_postings<'a>(
-) -> impl Parser<'a, &'a str, AutoPostings, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> AutoPostings<T> {
+    #[must_use]
+    pub fn into_owned(self) -> AutoPostings {
+        AutoPostings {
+            query: self.query.into_owned(),
+            postings: self
+                .postings
+                .into_iter()
+                .map(|posting| AutoPosting {
+                    account_name: posting.account_name.into_owned(),
+                    is_virtual: posting.is_virtual,
+                    amount: posting.amount.into_owned(),
+                    is_mul: posting.is_mul,
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn auto_postings<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, AutoPostings<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let header = just("=")
         .ignore_then(whitespace().repeated())
-        .ignore_then(query().then_ignore(end_of_line()))
+        .ignore_then(query().then_ignore(end_of_line::<T>()))
         .then_ignore(text::newline());
 
     let account_name = account_name()
@@ -40,15 +60,20 @@ pub fn auto_postings<'a>(
         .at_least(1)
         .ignore_then(account_name)
         .then_ignore(whitespace().repeated().at_least(2))
-        .then(just("*").or_not())
-        .then(amount())
-        .then_ignore(end_of_line())
+        .then(
+            // multipliers are not affected by default commodity
+            just("*")
+                .ignore_then(raw_amount())
+                .map(|amount| (true, amount))
+                .or(amount().map(|amount| (false, amount))),
+        )
+        .then_ignore(end_of_line::<T>())
         .map(
-            |(((account_name, is_virtual), is_mul), amount)| AutoPosting {
+            |((account_name, is_virtual), (is_mul, amount))| AutoPosting {
                 account_name,
                 is_virtual,
                 amount,
-                is_mul: is_mul.is_some(),
+                is_mul,
             },
         );
 
@@ -56,7 +81,7 @@ pub fn auto_postings<'a>(
         .then_ignore(
             text::whitespace()
                 .at_least(1)
-                .then(inline())
+                .then(inline::<T>())
                 .then_ignore(text::newline())
                 .or_not(),
         )
@@ -67,6 +92,7 @@ pub fn auto_postings<'a>(
                 .collect::<Vec<_>>(),
         )
         .map(|(query, postings)| AutoPostings { query, postings })
+        .boxed()
 }
 
 #[cfg(test)]
@@ -110,6 +136,7 @@ mod tests {
                             is_negative: true,
                             quantity: Quantity::from_u64(1),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         },
                     },
                     AutoPosting {
@@ -123,6 +150,7 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(1),
                             commodity: Commodity::from_str(""),
+                            ..Amount::default()
                         },
                     }
                 ],
@@ -1,20 +1,38 @@
 use chumsky::prelude::*;
 
-use crate::{component::whitespace::whitespace, state::State};
+use crate::{component::whitespace::whitespace, state::State, text::Text};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Query {
-    pub terms: Vec<Term>,
+pub struct Query<T = String> {
+    pub terms: Vec<Term<T>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Term {
-    pub r#type: Option<String>,
+pub struct Term<T = String> {
+    pub r#type: Option<T>,
     pub is_not: bool,
-    pub value: String,
+    pub value: T,
 }
 
-pub fn query<'a>() -> impl Parser<'a, &'a str, Query, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Query<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Query {
+        Query {
+            terms: self
+                .terms
+                .into_iter()
+                .map(|term| Term {
+                    r#type: term.r#type.map(Text::into_string),
+                    is_not: term.is_not,
+                    value: term.value.into_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn query<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Query<T>, extra::Full<Rich<'a, char>, State, ()>> {
     term()
         .separated_by(whitespace().repeated().at_least(1))
         .at_least(1)
@@ -22,19 +40,20 @@ pub fn query<'a>() -> impl Parser<'a, &'a str, Query, extra::Full<Rich<'a, char>
         .map(|terms| Query { terms })
 }
 
-fn term<'a>() -> impl Parser<'a, &'a str, Term, extra::Full<Rich<'a, char>, State, ()>> {
+fn term<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Term<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let value = any()
         .and_is(text::newline().not())
         .and_is(whitespace().not())
         .repeated()
         .at_least(1)
-        .collect::<String>();
+        .to_slice();
     let quoted_value = any()
         .and_is(text::newline().not())
         .and_is(just("'").not()) // indicated end of quote
         .repeated()
         .at_least(1)
-        .collect::<String>()
+        .to_slice()
         .delimited_by(just("'"), just("'"));
     let r#type = just("date")
         .or(just("status"))
@@ -42,7 +61,7 @@ fn term<'a>() -> impl Parser<'a, &'a str, Term, extra::Full<Rich<'a, char>, Stat
         .or(just("cur"))
         .or(just("amt"))
         .then_ignore(just(":"))
-        .map(ToString::to_string);
+        .map(T::from_slice);
 
     just("not:")
         .or_not()
@@ -51,7 +70,7 @@ fn term<'a>() -> impl Parser<'a, &'a str, Term, extra::Full<Rich<'a, char>, Stat
         .map(|((is_not, r#type), value)| Term {
             r#type,
             is_not: is_not.is_some(),
-            value,
+            value: T::from_slice(value),
         })
 }
 
@@ -1,31 +1,79 @@
 use chumsky::prelude::*;
 
-use crate::component::amount::{amount, Amount};
+use crate::component::amount::{raw_amount, Amount};
 use crate::component::commodity::{commodity as parse_commodity, Commodity as ParsedCommodity};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 #[derive(Clone, Debug, PartialEq)]
-pub enum Commodity {
-    Amount(Amount),
-    Commodity(ParsedCommodity),
+pub enum Commodity<T = String> {
+    Amount(Amount<T>),
+    Commodity(ParsedCommodity<T>),
 }
 
-pub fn commodity<'a>() -> impl Parser<'a, &'a str, Commodity, extra::Full<Rich<'a, char>, State, ()>>
-{
+impl<T> Commodity<T> {
+    #[must_use]
+    pub fn commodity(&self) -> &ParsedCommodity<T> {
+        match self {
+            Commodity::Amount(amount) => &amount.commodity,
+            Commodity::Commodity(commodity) => commodity,
+        }
+    }
+}
+
+impl<'a, T: Text<'a>> Commodity<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Commodity {
+        match self {
+            Commodity::Amount(amount) => Commodity::Amount(amount.into_owned()),
+            Commodity::Commodity(commodity) => Commodity::Commodity(commodity.into_owned()),
+        }
+    }
+}
+
+pub fn commodity<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Commodity<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    let format = text::newline()
+        .then(whitespace().repeated().at_least(1))
+        .ignore_then(just("format"))
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(raw_amount::<T>())
+        .then_ignore(end_of_line::<T>());
+    // commodity EUR
+    //   format 1.000,00 EUR
+    let symbol = parse_commodity()
+        .then_ignore(end_of_line::<T>())
+        .then(format.or_not())
+        .validate(|(symbol, format), e, emitter| match format {
+            Some(format) => {
+                if format.commodity != symbol {
+                    emitter.emit(Rich::custom(
+                        e.span(),
+                        format!(
+                            "commodity directive symbol {symbol} and format subdirective symbol {} should be the same.",
+                            format.commodity
+                        ),
+                    ));
+                }
+                Commodity::Amount(format)
+            }
+            None => Commodity::Commodity(symbol),
+        });
     just("commodity")
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(
-            amount()
+            raw_amount()
+                .then_ignore(end_of_line::<T>())
                 .map(Commodity::Amount)
-                .or(parse_commodity().map(Commodity::Commodity)),
+                .or(symbol),
         )
-        .then_ignore(end_of_line())
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::component::amount::style::{CommodityStyle, Side};
     use crate::component::quantity::Quantity;
 
     use super::*;
@@ -45,6 +93,7 @@ mod tests {
                     places: 2,
                 },
                 is_negative: false,
+                ..Amount::default()
             }))
         );
     }
@@ -64,6 +113,7 @@ mod tests {
                     places: 4,
                 },
                 is_negative: false,
+                ..Amount::default()
             }))
         );
     }
@@ -83,10 +133,53 @@ mod tests {
                     places: 0,
                 },
                 is_negative: false,
+                ..Amount::default()
             }))
         );
     }
 
+    #[test]
+    fn format_subdirective() {
+        let result = commodity()
+            .then_ignore(end())
+            .parse("commodity EUR  ; euro\n  format 1.000,00 EUR")
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            result,
+            Commodity::Amount(Amount {
+                commodity: ParsedCommodity::from_str("EUR"),
+                quantity: Quantity {
+                    mantissa: 100_000,
+                    places: 2,
+                },
+                ..Amount::default()
+            })
+        );
+        let Commodity::Amount(amount) = result else {
+            unreachable!()
+        };
+        assert_eq!(
+            amount.style,
+            CommodityStyle {
+                side: Side::Right,
+                is_spaced: true,
+                digit_group_mark: Some('.'),
+                decimal_mark: Some(','),
+                precision: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn format_subdirective_mismatch() {
+        let result = commodity::<String>()
+            .then_ignore(end())
+            .parse("commodity EUR\n  format 1.000,00 USD")
+            .into_result();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn just_currency() {
         let result = commodity()
@@ -11,8 +11,12 @@ pub fn decimal_mark<'a>(
 ) -> impl Parser<'a, &'a str, DecimalMark, extra::Full<Rich<'a, char>, State, ()>> {
     just("decimal-mark")
         .ignore_then(whitespace().repeated().at_least(1))
-        .ignore_then(one_of(".,"))
-        .then_ignore(end_of_line())
+        .ignore_then(one_of(".,").map_with(|mark, e| {
+            let state: &mut State = e.state();
+            state.decimal_mark = Some(mark);
+            mark
+        }))
+        .then_ignore(end_of_line::<String>())
         .map(DecimalMark)
 }
 
@@ -20,6 +24,17 @@ pub fn decimal_mark<'a>(
 mod tests {
     use super::*;
 
+    #[test]
+    fn should_update_state() {
+        let mut state = State::default();
+        let result = decimal_mark()
+            .then_ignore(end())
+            .parse_with_state("decimal-mark ,", &mut state)
+            .into_result();
+        assert_eq!(result, Ok(DecimalMark(',')));
+        assert_eq!(state.decimal_mark, Some(','));
+    }
+
     #[test]
     fn ok_trailing() {
         let result = decimal_mark()
@@ -0,0 +1,71 @@
+use chumsky::prelude::*;
+
+use crate::component::account_name::{account_name, AccountName};
+use crate::component::whitespace::whitespace;
+use crate::state::State;
+use crate::text::Text;
+use crate::utils::end_of_line;
+
+// account balancing transactions with a single posting
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultAccount<T = String> {
+    pub account_name: AccountName<T>,
+}
+
+impl<'a, T: Text<'a>> DefaultAccount<T> {
+    #[must_use]
+    pub fn into_owned(self) -> DefaultAccount {
+        DefaultAccount {
+            account_name: self.account_name.into_owned(),
+        }
+    }
+}
+
+pub fn default_account<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, DefaultAccount<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    just("bucket")
+        .or(just("A"))
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(account_name())
+        .then_ignore(end_of_line::<T>())
+        .map(|account_name| DefaultAccount { account_name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket() {
+        let result = default_account()
+            .then_ignore(end())
+            .parse("bucket assets:checking")
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(DefaultAccount {
+                account_name: AccountName::from_strs(&[
+                    String::from("assets"),
+                    String::from("checking")
+                ])
+            })
+        );
+    }
+
+    #[test]
+    fn short_form() {
+        let result = default_account()
+            .then_ignore(end())
+            .parse("A assets:checking  ; comment")
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(DefaultAccount {
+                account_name: AccountName::from_strs(&[
+                    String::from("assets"),
+                    String::from("checking")
+                ])
+            })
+        );
+    }
+}
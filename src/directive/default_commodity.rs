@@ -0,0 +1,108 @@
+use chumsky::prelude::*;
+
+use crate::component::amount::{raw_amount, Amount};
+use crate::component::whitespace::whitespace;
+use crate::state::State;
+use crate::text::Text;
+use crate::utils::end_of_line;
+
+// commodity and style for amounts written without commodity
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefaultCommodity<T = String> {
+    pub amount: Amount<T>,
+}
+
+impl<'a, T: Text<'a>> DefaultCommodity<T> {
+    #[must_use]
+    pub fn into_owned(self) -> DefaultCommodity {
+        DefaultCommodity {
+            amount: self.amount.into_owned(),
+        }
+    }
+}
+
+pub fn default_commodity<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, DefaultCommodity<T>, extra::Full<Rich<'a, char>, State, ()>> {
+    just("D")
+        .ignore_then(whitespace().repeated().at_least(1))
+        .ignore_then(raw_amount())
+        .then_ignore(end_of_line::<T>())
+        .map_with(|amount: Amount<T>, e| {
+            let state: &mut State = e.state();
+            state.default_commodity =
+                Some((amount.commodity.clone().into_owned(), amount.style.clone()));
+            DefaultCommodity { amount }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::amount::style::{CommodityStyle, Side};
+    use crate::component::commodity::Commodity;
+    use crate::component::quantity::Quantity;
+    use crate::directive::{directives, Directive};
+
+    use super::*;
+
+    #[test]
+    fn with_comment() {
+        let mut state = State::default();
+        let result = default_commodity()
+            .then_ignore(end())
+            .parse_with_state("D $1,000.00  ; default", &mut state)
+            .into_result();
+        assert_eq!(
+            result,
+            Ok(DefaultCommodity {
+                amount: Amount {
+                    quantity: Quantity {
+                        mantissa: 100_000,
+                        places: 2,
+                    },
+                    commodity: Commodity::from_str("$"),
+                    ..Amount::default()
+                }
+            })
+        );
+        assert_eq!(
+            state.default_commodity,
+            Some((
+                Commodity::from_str("$"),
+                CommodityStyle {
+                    side: Side::Left,
+                    is_spaced: false,
+                    digit_group_mark: Some(','),
+                    decimal_mark: Some('.'),
+                    precision: 2,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn applied_to_following_amounts() {
+        let result = directives::<String>()
+            .then_ignore(end())
+            .parse(
+                "2024-01-01
+    a  1
+D 1.000,00 EUR
+2024-01-02
+    a  1000
+    b  -5 gold",
+            )
+            .into_result()
+            .unwrap();
+        let amounts = result
+            .iter()
+            .filter_map(|directive| match directive {
+                Directive::Transaction(transaction) => Some(transaction),
+                _ => None,
+            })
+            .flat_map(|transaction| &transaction.postings)
+            .filter_map(|posting| posting.amount.as_ref())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(amounts, vec!["1", "1.000,00 EUR", "-5 gold"]);
+    }
+}
@@ -1,6 +1,6 @@
 use chumsky::prelude::*;
 
-mod format;
+pub mod format;
 
 use crate::component::whitespace::whitespace;
 use crate::directive::include::format::{format, Format};
@@ -24,7 +24,7 @@ pub fn include<'a>() -> impl Parser<'a, &'a str, Include, extra::Full<Rich<'a, c
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(format().then_ignore(just(":")).or_not())
         .then(path)
-        .then_ignore(end_of_line())
+        .then_ignore(end_of_line::<String>())
         .map(|(format, path)| Include {
             format,
             path: std::path::PathBuf::from(path.iter().collect::<String>().trim_end()),
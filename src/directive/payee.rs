@@ -2,12 +2,21 @@ use chumsky::prelude::*;
 
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Payee(String);
+pub struct Payee<T = String>(pub T);
 
-pub fn payee<'a>() -> impl Parser<'a, &'a str, Payee, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Payee<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Payee {
+        Payee(self.0.into_string())
+    }
+}
+
+pub fn payee<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Payee<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("payee")
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(
@@ -16,10 +25,10 @@ pub fn payee<'a>() -> impl Parser<'a, &'a str, Payee, extra::Full<Rich<'a, char>
                 .and_is(just(";").not())
                 .repeated()
                 .at_least(1)
-                .collect::<String>(),
+                .to_slice(),
         )
-        .then_ignore(end_of_line())
-        .map(|payee| Payee(payee.trim_end().to_string()))
+        .then_ignore(end_of_line::<T>())
+        .map(|payee: &str| Payee(T::from_slice(payee.trim_end())))
 }
 
 #[cfg(test)]
@@ -61,7 +70,10 @@ mod tests {
 
     #[test]
     fn err() {
-        let result = payee().then_ignore(end()).parse("paye Test").into_result();
+        let result = payee::<String>()
+            .then_ignore(end())
+            .parse("paye Test")
+            .into_result();
         assert!(result.is_err());
     }
 }
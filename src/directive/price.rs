@@ -3,30 +3,63 @@ use chumsky::prelude::*;
 use crate::component::amount::{amount, Amount};
 use crate::component::commodity::{commodity, Commodity};
 use crate::component::date::simple::date;
-use crate::component::time::time;
+use crate::component::time::{time, Time};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Price {
+pub struct Price<T = String> {
     pub date: chrono::NaiveDate,
-    pub commodity: Commodity,
-    pub amount: Amount,
+    pub time: Option<Time>,
+    pub commodity: Commodity<T>,
+    pub amount: Amount<T>,
 }
 
-pub fn price<'a>() -> impl Parser<'a, &'a str, Price, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Price<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Price {
+        Price {
+            date: self.date,
+            time: self.time,
+            commodity: self.commodity.into_owned(),
+            amount: self.amount.into_owned(),
+        }
+    }
+}
+
+impl<T> Price<T> {
+    // prices without time are at the start of the day
+    #[must_use]
+    pub fn date_time(&self) -> chrono::NaiveDateTime {
+        self.date.and_time(
+            self.time
+                .as_ref()
+                .map(Time::to_naive_time)
+                .unwrap_or_default(),
+        )
+    }
+}
+
+pub fn price<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Price<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("P")
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(date())
         .then_ignore(whitespace().repeated().at_least(1))
-        .then_ignore(time().then(whitespace().repeated().at_least(1)).or_not())
+        .then(
+            time()
+                .then_ignore(whitespace().repeated().at_least(1))
+                .or_not(),
+        )
         .then(commodity())
         .then_ignore(whitespace().repeated().at_least(1))
         .then(amount())
-        .then_ignore(end_of_line())
-        .map(|((date, commodity), amount)| Price {
+        .then_ignore(end_of_line::<T>())
+        .map(|(((date, time), commodity), amount)| Price {
             date,
+            time,
             commodity,
             amount,
         })
@@ -48,6 +81,7 @@ mod tests {
             result,
             Ok(Price {
                 date: chrono::NaiveDate::from_ymd_opt(2009, 1, 1).unwrap(),
+                time: None,
                 commodity: Commodity::from_str("€"),
                 amount: Amount {
                     quantity: Quantity {
@@ -71,6 +105,11 @@ mod tests {
             result,
             Ok(Price {
                 date: chrono::NaiveDate::from_ymd_opt(2024, 4, 18).unwrap(),
+                time: Some(Time {
+                    hours: 0,
+                    minutes: 0,
+                    seconds: 0,
+                }),
                 commodity: Commodity::from_str("BTC"),
                 amount: Amount {
                     quantity: Quantity {
@@ -94,6 +133,7 @@ mod tests {
             result,
             Ok(Price {
                 date: chrono::NaiveDate::from_ymd_opt(2009, 1, 1).unwrap(),
+                time: None,
                 commodity: Commodity::from_str("€"),
                 amount: Amount {
                     quantity: Quantity {
@@ -2,12 +2,21 @@ use chumsky::prelude::*;
 
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Tag(String);
+pub struct Tag<T = String>(pub T);
 
-pub fn tag<'a>() -> impl Parser<'a, &'a str, Tag, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Tag<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Tag {
+        Tag(self.0.into_string())
+    }
+}
+
+pub fn tag<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Tag<T>, extra::Full<Rich<'a, char>, State, ()>> {
     just("tag")
         .ignore_then(whitespace().repeated().at_least(1))
         .ignore_then(
@@ -17,10 +26,10 @@ pub fn tag<'a>() -> impl Parser<'a, &'a str, Tag, extra::Full<Rich<'a, char>, St
                 .and_is(whitespace().not())
                 .repeated()
                 .at_least(1)
-                .collect::<String>(),
+                .to_slice(),
         )
-        .then_ignore(end_of_line())
-        .map(|tag| Tag(tag.trim_end().to_string()))
+        .then_ignore(end_of_line::<T>())
+        .map(|tag: &str| Tag(T::from_slice(tag.trim_end())))
 }
 
 #[cfg(test)]
@@ -44,7 +53,7 @@ mod tests {
 
     #[test]
     fn err_with_space() {
-        let result = tag()
+        let result = tag::<String>()
             .then_ignore(end())
             .parse("tag Testing things")
             .into_result();
@@ -59,7 +68,10 @@ mod tests {
 
     #[test]
     fn err() {
-        let result = tag().then_ignore(end()).parse("t Test").into_result();
+        let result = tag::<String>()
+            .then_ignore(end())
+            .parse("t Test")
+            .into_result();
         assert!(result.is_err());
     }
 }
@@ -1,8 +1,8 @@
-mod header;
-mod periodic;
-mod posting;
-mod simple;
-mod status;
+pub mod header;
+pub mod periodic;
+pub mod posting;
+pub mod simple;
+pub mod status;
 
 pub use periodic::{transaction as periodic, Transaction as Periodic};
 pub use simple::{transaction as simple, Transaction as Simple};
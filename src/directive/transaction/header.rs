@@ -1,25 +1,29 @@
 use chumsky::prelude::*;
 
+use crate::component::comment::Comment;
 use crate::component::whitespace::whitespace;
 use crate::directive::transaction::status::{status, Status};
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Header {
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Header<T = String> {
     pub status: Option<Status>,
-    pub code: Option<String>,
-    pub payee: String,
-    pub description: Option<String>,
+    pub code: Option<T>,
+    pub payee: T,
+    pub description: Option<T>,
+    pub comment: Option<Comment<T>>,
 }
 
-pub fn header<'a>() -> impl Parser<'a, &'a str, Header, extra::Full<Rich<'a, char>, State, ()>> {
+pub fn header<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Header<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let code = any()
         .and_is(text::newline().not())
         .and_is(just(")").not()) // forbidden, because it indicates end of the code
         .repeated()
         .at_least(1)
-        .collect::<String>()
+        .to_slice()
         .delimited_by(just('('), just(')'));
 
     let payee = any()
@@ -27,14 +31,14 @@ pub fn header<'a>() -> impl Parser<'a, &'a str, Header, extra::Full<Rich<'a, cha
         .and_is(just("|").not()) // forbidden, because it is a description separator
         .and_is(just(";").not()) // forbidden, because it indicates comment
         .repeated()
-        .collect::<String>();
+        .to_slice();
 
     let description = just("|").ignore_then(whitespace().repeated()).ignore_then(
         any()
             .and_is(text::newline().not())
             .and_is(just(";").not()) // forbidden, because it indicates comment
             .repeated()
-            .collect::<String>(),
+            .to_slice(),
     );
 
     status()
@@ -42,11 +46,13 @@ pub fn header<'a>() -> impl Parser<'a, &'a str, Header, extra::Full<Rich<'a, cha
         .then(whitespace().repeated().ignore_then(code).or_not())
         .then(whitespace().repeated().ignore_then(payee))
         .then(whitespace().repeated().ignore_then(description).or_not())
-        .then_ignore(end_of_line())
-        .map(|(((status, code), payee), description)| Header {
+        .then(end_of_line())
+        .map(|((((status, code), payee), description), comment)| Header {
             status,
-            code,
-            payee: payee.trim().to_string(),
-            description,
+            code: code.map(T::from_slice),
+            payee: T::from_slice(payee.trim()),
+            description: description.map(T::from_slice),
+            comment,
         })
+        .boxed()
 }
@@ -6,26 +6,48 @@ use crate::component::whitespace::whitespace;
 use crate::directive::transaction::posting::{posting, Posting};
 use crate::directive::transaction::status::Status;
 use crate::state::State;
+use crate::text::Text;
+use crate::utils::end_of_line;
 
 use super::header::header;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Transaction {
+pub struct Transaction<T = String> {
     pub period: Period,
     pub status: Option<Status>,
-    pub code: Option<String>,
-    pub payee: String,
-    pub description: Option<String>,
-    pub postings: Vec<Posting>,
+    pub code: Option<T>,
+    pub payee: T,
+    pub description: Option<T>,
+    pub postings: Vec<Posting<T>>,
 }
 
-pub fn transaction<'a>(
-) -> impl Parser<'a, &'a str, Transaction, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Transaction<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Transaction {
+        Transaction {
+            period: self.period,
+            status: self.status,
+            code: self.code.map(Text::into_string),
+            payee: self.payee.into_string(),
+            description: self.description.map(Text::into_string),
+            postings: self.postings.into_iter().map(Posting::into_owned).collect(),
+        }
+    }
+}
+
+pub fn transaction<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Transaction<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let header = just("~")
         .ignore_then(whitespace().repeated())
         .ignore_then(period())
-        .then_ignore(whitespace().repeated().at_least(2))
-        .then(header().or_not());
+        .then(
+            whitespace()
+                .repeated()
+                .at_least(2)
+                .ignore_then(header())
+                .map(Some)
+                .or(end_of_line::<T>().to(None)),
+        );
 
     header
         .then_ignore(
@@ -33,7 +55,7 @@ pub fn transaction<'a>(
                 .then(
                     text::whitespace()
                         .at_least(1)
-                        .then(inline())
+                        .then(inline::<T>())
                         .then_ignore(text::newline()),
                 )
                 .or_not(),
@@ -44,14 +66,18 @@ pub fn transaction<'a>(
                 .allow_leading()
                 .collect::<Vec<_>>(),
         )
-        .map(|((period, header), postings)| Transaction {
-            period,
-            status: header.as_ref().and_then(|h| h.status.clone()),
-            code: header.as_ref().and_then(|h| h.code.clone()),
-            payee: header.as_ref().map_or(String::new(), |h| h.payee.clone()),
-            description: header.as_ref().and_then(|h| h.description.clone()),
-            postings,
+        .map(|((period, header), postings)| {
+            let header = header.unwrap_or_default();
+            Transaction {
+                period,
+                status: header.status,
+                code: header.code,
+                payee: header.payee,
+                description: header.description,
+                postings,
+            }
         })
+        .boxed()
 }
 
 #[cfg(test)]
@@ -96,10 +122,13 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(400),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         }),
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: None,
                     },
                     Posting {
                         status: None,
@@ -112,6 +141,8 @@ mod tests {
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: None,
                     }
                 ],
             })
@@ -151,10 +182,13 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(1000),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         }),
                         price: None,
                         assertion: None,
                         is_virtual: true,
+                        lot: None,
+                        comment: None,
                     },
                     Posting {
                         status: None,
@@ -166,13 +200,31 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(500),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         }),
                         price: None,
                         assertion: None,
                         is_virtual: true,
+                        lot: None,
+                        comment: None,
                     }
                 ],
             })
         );
     }
+
+    #[test]
+    fn without_description() {
+        let result = transaction::<String>()
+            .then_ignore(end())
+            .parse(
+                "~ every 2 weeks  ; no description
+    (expenses:rent)      $1000",
+            )
+            .into_result()
+            .unwrap();
+        assert_eq!(result.period.interval, Some(Interval::NthWeek(2)));
+        assert_eq!(result.payee, "");
+        assert_eq!(result.postings.len(), 1);
+    }
 }
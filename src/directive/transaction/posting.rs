@@ -1,30 +1,53 @@
 use chumsky::prelude::*;
 
-mod assertion;
+pub mod assertion;
 
 use crate::component::account_name::{account_name, AccountName};
 use crate::component::amount::{amount, Amount};
+use crate::component::comment::Comment;
+use crate::component::lot::{lot, Lot};
 use crate::component::price::{price, Price};
 use crate::component::whitespace::whitespace;
 use crate::directive::transaction::status::{status, Status};
 use crate::state::State;
+use crate::text::Text;
 use crate::utils::end_of_line;
 
 use self::assertion::{assertion, Assertion};
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Posting {
+pub struct Posting<T = String> {
     pub status: Option<Status>,
-    pub account_name: AccountName,
+    pub account_name: AccountName<T>,
     pub is_virtual: bool,
-    pub amount: Option<Amount>,
-    pub price: Option<Price>,
-    pub assertion: Option<Assertion>,
+    pub amount: Option<Amount<T>>,
+    pub lot: Option<Lot<T>>,
+    pub price: Option<Price<T>>,
+    pub assertion: Option<Assertion<T>>,
+    pub comment: Option<Comment<T>>,
+}
+
+impl<'a, T: Text<'a>> Posting<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Posting {
+        Posting {
+            status: self.status,
+            account_name: self.account_name.into_owned(),
+            is_virtual: self.is_virtual,
+            amount: self.amount.map(Amount::into_owned),
+            lot: self.lot.map(Lot::into_owned),
+            price: self.price.map(Price::into_owned),
+            assertion: self.assertion.map(Assertion::into_owned),
+            comment: self.comment.map(Comment::into_owned),
+        }
+    }
 }
 
 #[must_use]
-pub fn posting<'a>() -> impl Parser<'a, &'a str, Posting, extra::Full<Rich<'a, char>, State, ()>> {
+pub fn posting<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Posting<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let posting_amount = whitespace().repeated().at_least(2).ignore_then(amount());
+    let posting_lot = whitespace().repeated().ignore_then(lot());
     let posting_price = whitespace().repeated().ignore_then(price());
     let posting_assertion = whitespace().repeated().ignore_then(assertion());
     let account_name = account_name()
@@ -36,20 +59,26 @@ pub fn posting<'a>() -> impl Parser<'a, &'a str, Posting, extra::Full<Rich<'a, c
         .at_least(1)
         .ignore_then(status().then_ignore(whitespace()).or_not())
         .then(account_name)
-        .then(posting_amount.or_not())
+        .then(posting_amount.then(posting_lot.or_not()).or_not())
         .then(posting_price.or_not())
         .then(posting_assertion.or_not())
-        .then_ignore(end_of_line())
+        .then(end_of_line())
         .map(
-            |((((status, (account_name, is_virtual)), amount), price), assertion)| Posting {
-                status,
-                account_name,
-                is_virtual,
-                amount,
-                price,
-                assertion,
+            |(((((status, (account_name, is_virtual)), amount), price), assertion), comment)| {
+                let (amount, lot) = amount.unzip();
+                Posting {
+                    status,
+                    account_name,
+                    is_virtual,
+                    amount,
+                    lot: lot.flatten(),
+                    price,
+                    assertion,
+                    comment,
+                }
             },
         )
+        .boxed()
 }
 
 #[cfg(test)]
@@ -77,10 +106,13 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
+                    ..Amount::default()
                 }),
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -104,6 +136,8 @@ mod tests {
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -127,10 +161,13 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
+                    ..Amount::default()
                 }),
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -157,6 +194,10 @@ mod tests {
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: Some(Comment(String::from(
+                    " some comment\n continuation of the same comment"
+                ))),
             })
         );
     }
@@ -180,6 +221,8 @@ mod tests {
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -203,11 +246,13 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("EUR"),
+                    ..Amount::default()
                 }),
                 price: Some(Price::Total(Amount {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("USD"),
+                    ..Amount::default()
                 })),
                 assertion: Some(Assertion {
                     price: None,
@@ -215,11 +260,14 @@ mod tests {
                         is_negative: false,
                         quantity: Quantity::from_u64(1),
                         commodity: Commodity::from_str("USD"),
+                        ..Amount::default()
                     },
                     is_subaccount_inclusive: false,
                     is_strict: false,
                 }),
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -243,6 +291,7 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("USD"),
+                    ..Amount::default()
                 }),
                 price: None,
                 assertion: Some(Assertion {
@@ -251,11 +300,14 @@ mod tests {
                         is_negative: false,
                         quantity: Quantity::from_u64(1),
                         commodity: Commodity::from_str("USD"),
+                        ..Amount::default()
                     },
                     is_subaccount_inclusive: false,
                     is_strict: true,
                 }),
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -279,14 +331,18 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("USD"),
+                    ..Amount::default()
                 }),
                 price: Some(Price::Unit(Amount {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("EUR"),
+                    ..Amount::default()
                 })),
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -310,10 +366,13 @@ mod tests {
                     is_negative: false,
                     quantity: Quantity::from_u64(1),
                     commodity: Commodity::from_str("$"),
+                    ..Amount::default()
                 }),
                 price: None,
                 assertion: None,
                 is_virtual: true,
+                lot: None,
+                comment: None,
             })
         );
     }
@@ -337,13 +396,38 @@ mod tests {
                 price: None,
                 assertion: None,
                 is_virtual: false,
+                lot: None,
+                comment: None,
             })
         );
     }
 
     #[test]
-    fn no_ident() {
+    fn with_lot() {
         let result = posting()
+            .then_ignore(end())
+            .parse("  assets:stock  -4 AAPL {$50} [2024-01-05] @ $60")
+            .into_result()
+            .unwrap();
+        assert_eq!(
+            result.lot.and_then(|lot| lot.cost),
+            Some(Price::Unit(Amount {
+                is_negative: false,
+                quantity: Quantity::from_u64(50),
+                commodity: Commodity::from_str("$"),
+                ..Amount::default()
+            }))
+        );
+        assert!(matches!(result.price, Some(Price::Unit(_))));
+        assert_eq!(
+            result.amount.map(|amount| amount.commodity),
+            Some(Commodity::from_str("AAPL"))
+        );
+    }
+
+    #[test]
+    fn no_ident() {
+        let result = posting::<String>()
             .then_ignore(end())
             .parse("assets:bank:checking $1")
             .into_result();
@@ -4,17 +4,30 @@ use crate::component::amount::{amount, Amount};
 use crate::component::price::{price, Price};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Assertion {
+pub struct Assertion<T = String> {
     pub is_strict: bool,
     pub is_subaccount_inclusive: bool,
-    pub amount: Amount,
-    pub price: Option<Price>,
+    pub amount: Amount<T>,
+    pub price: Option<Price<T>>,
 }
 
-pub fn assertion<'a>() -> impl Parser<'a, &'a str, Assertion, extra::Full<Rich<'a, char>, State, ()>>
-{
+impl<'a, T: Text<'a>> Assertion<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Assertion {
+        Assertion {
+            is_strict: self.is_strict,
+            is_subaccount_inclusive: self.is_subaccount_inclusive,
+            amount: self.amount.into_owned(),
+            price: self.price.map(Price::into_owned),
+        }
+    }
+}
+
+pub fn assertion<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Assertion<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let price = whitespace().repeated().ignore_then(price());
     just("=")
         .repeated()
@@ -56,11 +69,13 @@ mod tests {
                     is_negative: false,
                     commodity: Commodity::from_str("$"),
                     quantity: crate::component::quantity::Quantity::from_u64(1),
+                    ..Amount::default()
                 },
                 price: Some(Price::Total(Amount {
                     is_negative: false,
                     commodity: Commodity::from_str("USD"),
                     quantity: crate::component::quantity::Quantity::from_u64(5),
+                    ..Amount::default()
                 })),
             })
         );
@@ -78,6 +93,7 @@ mod tests {
                     is_negative: false,
                     commodity: Commodity::from_str("$"),
                     quantity: crate::component::quantity::Quantity::from_u64(1),
+                    ..Amount::default()
                 },
                 price: None,
             })
@@ -96,6 +112,7 @@ mod tests {
                     is_negative: false,
                     commodity: Commodity::from_str("$"),
                     quantity: crate::component::quantity::Quantity::from_u64(1),
+                    ..Amount::default()
                 },
                 price: None,
             })
@@ -114,6 +131,7 @@ mod tests {
                     is_negative: false,
                     commodity: Commodity::from_str("$"),
                     quantity: crate::component::quantity::Quantity::from_u64(1),
+                    ..Amount::default()
                 },
                 price: None,
             })
@@ -132,6 +150,7 @@ mod tests {
                     is_negative: false,
                     commodity: Commodity::from_str("$"),
                     quantity: crate::component::quantity::Quantity::from_u64(1),
+                    ..Amount::default()
                 },
                 price: None,
             })
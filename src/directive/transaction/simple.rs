@@ -1,36 +1,57 @@
 use chumsky::prelude::*;
 
-use crate::component::comment::inline;
+use crate::component::comment::{inline, Comment};
 use crate::component::date::simple::date;
 use crate::component::whitespace::whitespace;
 use crate::directive::transaction::header::header;
 use crate::directive::transaction::posting::{posting, Posting};
 use crate::directive::transaction::status::Status;
 use crate::state::State;
+use crate::text::Text;
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Transaction {
+pub struct Transaction<T = String> {
     pub date: chrono::NaiveDate,
     pub status: Option<Status>,
-    pub code: Option<String>,
-    pub payee: String,
-    pub description: Option<String>,
-    pub postings: Vec<Posting>,
+    pub code: Option<T>,
+    pub payee: T,
+    pub description: Option<T>,
+    pub postings: Vec<Posting<T>>,
+    // comment after the header, or on the lines below it
+    pub comment: Option<Comment<T>>,
+    // generated from a periodic transaction rather than written in the journal
+    pub is_generated: bool,
 }
 
-pub fn transaction<'a>(
-) -> impl Parser<'a, &'a str, Transaction, extra::Full<Rich<'a, char>, State, ()>> {
+impl<'a, T: Text<'a>> Transaction<T> {
+    #[must_use]
+    pub fn into_owned(self) -> Transaction {
+        Transaction {
+            date: self.date,
+            status: self.status,
+            code: self.code.map(Text::into_string),
+            payee: self.payee.into_string(),
+            description: self.description.map(Text::into_string),
+            postings: self.postings.into_iter().map(Posting::into_owned).collect(),
+            comment: self.comment.map(Comment::into_owned),
+            is_generated: self.is_generated,
+        }
+    }
+}
+
+pub fn transaction<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Transaction<T>, extra::Full<Rich<'a, char>, State, ()>> {
     let header = date()
         .then_ignore(whitespace().repeated())
         .then(header().or_not());
 
     header
-        .then_ignore(
+        .then(
             text::newline()
-                .then(
+                .ignore_then(
                     text::whitespace()
                         .at_least(1)
-                        .then(inline())
+                        .ignore_then(inline())
                         .then_ignore(text::newline()),
                 )
                 .or_not(),
@@ -41,14 +62,20 @@ pub fn transaction<'a>(
                 .allow_leading()
                 .collect::<Vec<_>>(),
         )
-        .map(|((date, header), postings)| Transaction {
-            date,
-            status: header.as_ref().and_then(|h| h.status.clone()),
-            code: header.as_ref().and_then(|h| h.code.clone()),
-            payee: header.as_ref().map_or(String::new(), |h| h.payee.clone()),
-            description: header.as_ref().and_then(|h| h.description.clone()),
-            postings,
+        .map(|(((date, header), comment), postings)| {
+            let header = header.unwrap_or_default();
+            Transaction {
+                date,
+                status: header.status,
+                code: header.code,
+                payee: header.payee,
+                description: header.description,
+                comment: header.comment.or(comment),
+                postings,
+                is_generated: false,
+            }
         })
+        .boxed()
 }
 
 #[cfg(test)]
@@ -91,10 +118,15 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(1),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         }),
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: Some(Comment(String::from(
+                            " posting comment\n same comment second line"
+                        ))),
                     },
                     Posting {
                         status: None,
@@ -106,8 +138,14 @@ mod tests {
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: None,
                     }
                 ],
+                is_generated: false,
+                comment: Some(Comment(String::from(
+                    " transaction comment\n same comment second line"
+                ))),
             })
         );
     }
@@ -142,10 +180,13 @@ mod tests {
                             is_negative: false,
                             quantity: Quantity::from_u64(1),
                             commodity: Commodity::from_str("$"),
+                            ..Amount::default()
                         }),
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: None,
                     },
                     Posting {
                         status: None,
@@ -157,12 +198,26 @@ mod tests {
                         price: None,
                         assertion: None,
                         is_virtual: false,
+                        lot: None,
+                        comment: None,
                     }
                 ],
+                is_generated: false,
+                comment: None,
             })
         );
     }
 
+    #[test]
+    fn single_posting() {
+        let result = transaction::<String>()
+            .then_ignore(end())
+            .parse("2008/01/01 salary\n    income:salary  $-1")
+            .into_result()
+            .unwrap();
+        assert_eq!(result.postings.len(), 1);
+    }
+
     #[test]
     fn just_date() {
         let result = transaction()
@@ -178,6 +233,8 @@ mod tests {
                 payee: String::new(),
                 description: None,
                 postings: vec![],
+                is_generated: false,
+                comment: None,
             })
         );
     }
@@ -16,14 +16,16 @@ pub fn year<'a>() -> impl Parser<'a, &'a str, Year, extra::Full<Rich<'a, char>,
                 .repeated()
                 .at_least(1)
                 .collect::<String>()
-                .map_with(|p, e| {
+                .try_map_with(|p, e| {
+                    let year = p
+                        .parse::<i32>()
+                        .map_err(|_| Rich::custom(e.span(), format!("{p} is not a valid year.")))?;
                     let state: &mut State = e.state();
-                    let year = p.parse::<i32>().unwrap();
                     state.year = year;
-                    year
+                    Ok(year)
                 }),
         )
-        .then_ignore(end_of_line())
+        .then_ignore(end_of_line::<String>())
         .map(Year)
 }
 
@@ -33,7 +35,10 @@ mod tests {
 
     #[test]
     fn should_update_state() {
-        let mut state = State { year: 1 };
+        let mut state = State {
+            year: 1,
+            ..State::default()
+        };
         let result = year()
             .then_ignore(end())
             .parse_with_state("Y2024", &mut state)
@@ -51,6 +56,15 @@ mod tests {
         assert_eq!(result, Ok(Year(2024)));
     }
 
+    #[test]
+    fn too_large() {
+        let result = year()
+            .then_ignore(end())
+            .parse("Y99999999999")
+            .into_result();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn ok_with_comment() {
         let result = year()
@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+
+use rust_decimal::Decimal;
+
+use crate::component::commodity::Commodity;
+use crate::directive::transaction::Simple as Transaction;
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::report::entries;
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    // largest number of days between the dates of duplicates
+    pub days: u32,
+    // smallest score of reported duplicates, from 0 to 1
+    pub min_score: f64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            days: 3,
+            min_score: 0.5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Duplicate<'a> {
+    pub first: &'a Transaction,
+    pub second: &'a Transaction,
+    // similarity of payee and description, lowered by the distance between the dates
+    pub score: f64,
+}
+
+// pairs of transactions with the same amount, close dates and similar text, most likely first
+#[must_use]
+pub fn duplicates<'a>(directives: &'a [Directive], options: &Options) -> Vec<Duplicate<'a>> {
+    let mut transactions = directives
+        .iter()
+        .filter_map(|directive| match directive {
+            // amounts too large to sum can't be compared
+            Directive::Transaction(transaction) => Some((
+                transaction,
+                amount(directive).ok()?,
+                bigrams(&normalized(transaction)),
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    transactions.sort_by_key(|(transaction, _, _)| transaction.date);
+
+    let mut duplicates = Vec::new();
+    for (index, (first, amount, text)) in transactions.iter().enumerate() {
+        for (second, other_amount, other_text) in &transactions[index + 1..] {
+            let days = (second.date - first.date).num_days();
+            if days > i64::from(options.days) {
+                break;
+            }
+            if amount != other_amount {
+                continue;
+            }
+            #[allow(clippy::cast_precision_loss)]
+            let closeness = 1.0 - days as f64 / (f64::from(options.days) + 1.0);
+            let score = similarity(text, other_text) * closeness;
+            if score >= options.min_score {
+                duplicates.push(Duplicate {
+                    first,
+                    second,
+                    score,
+                });
+            }
+        }
+    }
+    duplicates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    duplicates
+}
+
+// sum of the transaction's increases in each commodity, with inferred amounts
+fn amount(directive: &Directive) -> Result<Vec<(Commodity, Decimal)>, Overflow> {
+    let mut sum = MixedAmount::default();
+    for entry in entries(std::slice::from_ref(directive))? {
+        for amount in entry.amount.amounts() {
+            if !amount.is_negative && !entry.posting.is_virtual {
+                sum.add(amount)?;
+            }
+        }
+    }
+    Ok(sum
+        .amounts()
+        .map(|amount| (amount.commodity.clone(), amount.to_decimal()))
+        .collect())
+}
+
+// payee and description in lowercase, with only letters and digits separated by single spaces
+fn normalized(transaction: &Transaction) -> String {
+    let text = format!(
+        "{} {}",
+        transaction.payee,
+        transaction.description.as_deref().unwrap_or_default()
+    );
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(text: &str) -> HashSet<(char, char)> {
+    let chars = text.chars().collect::<Vec<_>>();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+// dice coefficient of the character pairs, 1 when both texts are equal
+fn similarity(a: &HashSet<(char, char)>, b: &HashSet<(char, char)>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let score = 2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64;
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similar_transactions() {
+        let directives = crate::parse(
+            "2024-01-01 Coffee Shop
+    expenses:coffee  $4.50
+    assets:bank
+2024-01-02 COFFEE-SHOP
+    expenses:unknown  $4.50
+    assets:bank
+2024-01-02 coffee shop
+    expenses:coffee  $5
+    assets:bank
+2024-01-09 Coffee Shop
+    expenses:coffee  $4.50
+    assets:bank
+2024-01-01 Book store
+    expenses:books  $4.50
+    assets:bank
+",
+        )
+        .unwrap();
+        let duplicates = duplicates(&directives, &Options::default())
+            .into_iter()
+            .map(|duplicate| {
+                (
+                    duplicate.first.payee.clone(),
+                    duplicate.second.payee.clone(),
+                    (duplicate.score * 100.0).round(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            duplicates,
+            vec![(
+                String::from("Coffee Shop"),
+                String::from("COFFEE-SHOP"),
+                75.0
+            )]
+        );
+    }
+
+    #[test]
+    fn same_day() {
+        let directives = crate::parse(
+            "2024-03-01 rent | march
+    expenses:rent  $1000
+    assets:bank  $-1000
+2024-03-01 Rent | March
+    expenses:rent
+    assets:bank  $-1000
+",
+        )
+        .unwrap();
+        let duplicates = duplicates(&directives, &Options::default());
+        assert_eq!(duplicates.len(), 1);
+        assert!((duplicates[0].score - 1.0).abs() < f64::EPSILON);
+    }
+}
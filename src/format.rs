@@ -0,0 +1,474 @@
+use std::collections::HashMap;
+
+use chumsky::prelude::*;
+
+use crate::component::amount::{raw_amount, style::CommodityStyle, Amount};
+use crate::component::commodity::Commodity;
+use crate::state::State;
+use crate::styles::commodity_styles;
+
+// journal with transactions in a canonical layout: dates written as yyyy-mm-dd, single spaces
+// around status markers, postings indented by four spaces with their amounts aligned, and amounts
+// in the style of their commodity. comments, comment blocks and other directives are kept as they
+// are, and so are line endings.
+#[allow(clippy::missing_errors_doc)]
+pub fn format(contents: &str) -> Result<String, Vec<Rich<'_, char, SimpleSpan>>> {
+    let styles = commodity_styles(&crate::parse(contents)?);
+    let mut formatted = String::with_capacity(contents.len());
+    let mut transaction = Vec::<(Line, &str)>::new();
+    let mut in_comment_block = false;
+    // amounts are read with the decimal mark declared above them
+    let mut decimal_mark = None;
+    for (line, newline) in lines(contents) {
+        if in_comment_block || line == "comment" {
+            write_transaction(&mut formatted, &mut transaction);
+            in_comment_block = line != "end comment";
+            formatted.push_str(line);
+            formatted.push_str(newline);
+            continue;
+        }
+        let is_indented = line.starts_with([' ', '\t']);
+        if is_indented && !transaction.is_empty() {
+            transaction.push((body_line(line, &styles, decimal_mark), newline));
+            continue;
+        }
+        write_transaction(&mut formatted, &mut transaction);
+        if let Some(mark) = line.strip_prefix("decimal-mark") {
+            decimal_mark = mark.trim_start().chars().next().or(decimal_mark);
+        }
+        if let Some(header) = header(line) {
+            transaction.push((Line::Other(header), newline));
+        } else {
+            formatted.push_str(line);
+            formatted.push_str(newline);
+        }
+    }
+    write_transaction(&mut formatted, &mut transaction);
+    Ok(formatted)
+}
+
+// lines with their line ending, which is "\n" for a last line without one
+fn lines(contents: &str) -> impl Iterator<Item = (&str, &str)> {
+    contents.split_inclusive('\n').map(|line| {
+        line.strip_suffix("\r\n")
+            .map(|line| (line, "\r\n"))
+            .or_else(|| line.strip_suffix('\n').map(|line| (line, "\n")))
+            .unwrap_or((line, "\n"))
+    })
+}
+
+enum Line {
+    Other(String),
+    Posting {
+        // indentation, status and account name
+        account: String,
+        // amount and everything after it, aligned after the longest account
+        rest: String,
+    },
+}
+
+fn write_transaction(formatted: &mut String, transaction: &mut Vec<(Line, &str)>) {
+    let width = transaction
+        .iter()
+        .filter_map(|(line, _)| match line {
+            Line::Posting { account, rest } if !rest.is_empty() => Some(account.chars().count()),
+            _ => None,
+        })
+        .max()
+        .unwrap_or_default();
+    for (line, newline) in transaction.drain(..) {
+        match line {
+            Line::Other(line) => formatted.push_str(&line),
+            Line::Posting { account, rest } if rest.is_empty() => formatted.push_str(&account),
+            Line::Posting { account, rest } => {
+                let padding = width - account.chars().count() + 2;
+                formatted.push_str(&account);
+                formatted.push_str(&" ".repeat(padding));
+                formatted.push_str(&rest);
+            }
+        }
+        formatted.push_str(newline);
+    }
+}
+
+// transaction header with a normalized date and status, or periodic transaction header as it is
+fn header(line: &str) -> Option<String> {
+    if line.starts_with('~') {
+        return Some(line.trim_end().to_string());
+    }
+    let date_end = line.find(char::is_whitespace).unwrap_or(line.len());
+    let date = normalized_date(&line[..date_end])?;
+    let rest = line[date_end..].trim();
+    let (status, rest) = match rest.chars().next() {
+        Some(status @ ('*' | '!')) => (Some(status), rest[1..].trim_start()),
+        _ => (None, rest),
+    };
+    let mut header = date;
+    for part in status
+        .map(String::from)
+        .into_iter()
+        .chain((!rest.is_empty()).then(|| rest.to_string()))
+    {
+        header.push(' ');
+        header.push_str(&part);
+    }
+    Some(header)
+}
+
+// date with '-', '/' or '.' separators as yyyy-mm-dd, keeping a secondary date after '='
+fn normalized_date(date: &str) -> Option<String> {
+    let (primary, secondary) = match date.split_once('=') {
+        Some((primary, secondary)) => (primary, Some(secondary)),
+        None => (date, None),
+    };
+    let separator = primary.chars().find(|c| matches!(c, '-' | '/' | '.'))?;
+    let parts = primary.split(separator).collect::<Vec<_>>();
+    if parts
+        .iter()
+        .any(|part| part.is_empty() || part.len() > 4 || !part.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+    let mut normalized = match parts.as_slice() {
+        [year, month, day] => format!("{year}-{month:0>2}-{day:0>2}"),
+        [month, day] => format!("{month:0>2}-{day:0>2}"),
+        _ => return None,
+    };
+    if let Some(secondary) = secondary {
+        normalized.push('=');
+        normalized.push_str(&normalized_date(secondary).unwrap_or(secondary.to_string()));
+    }
+    Some(normalized)
+}
+
+// posting, or comment, indented by four spaces
+fn body_line(
+    line: &str,
+    styles: &HashMap<Commodity, CommodityStyle>,
+    decimal_mark: Option<char>,
+) -> Line {
+    let line = line.trim();
+    if line.starts_with(';') {
+        return Line::Other(format!("    {line}"));
+    }
+    let (status, line) = match line.chars().next() {
+        Some(status @ ('*' | '!')) => (format!("{status} "), line[1..].trim_start()),
+        _ => (String::new(), line),
+    };
+    let account_end = [line.find("  "), line.find('\t')]
+        .into_iter()
+        .flatten()
+        .min()
+        .unwrap_or(line.len());
+    let account = format!("    {status}{}", &line[..account_end]);
+    let rest = line[account_end..].trim();
+    if rest.starts_with(';') {
+        return Line::Other(format!("{account}  {rest}"));
+    }
+    Line::Posting {
+        account,
+        rest: styled(rest, styles, decimal_mark),
+    }
+}
+
+// posting amount and balance assertion amount in their commodity's style, with single spaces
+// around the assertion's "="
+fn styled(
+    text: &str,
+    styles: &HashMap<Commodity, CommodityStyle>,
+    decimal_mark: Option<char>,
+) -> String {
+    let Some((amount, marker, assertion)) = split_assertion(text) else {
+        return styled_amount(text, styles, decimal_mark);
+    };
+    let amount = styled_amount(amount.trim(), styles, decimal_mark);
+    let assertion = styled_amount(assertion.trim(), styles, decimal_mark);
+    if amount.is_empty() {
+        format!("{marker} {assertion}")
+    } else {
+        format!("{amount} {marker} {assertion}")
+    }
+}
+
+// text before the balance assertion, its "=", "==", "=*" or "==*", and the text after it. an
+// "=" in a quoted commodity or in a comment is not an assertion.
+fn split_assertion(text: &str) -> Option<(&str, &str, &str)> {
+    let mut is_quoted = false;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => is_quoted = !is_quoted,
+            ';' if !is_quoted => return None,
+            '=' if !is_quoted => {
+                let rest = &text[index..];
+                let marker = ["==*", "==", "=*", "="]
+                    .into_iter()
+                    .find(|marker| rest.starts_with(marker))?;
+                return Some((&text[..index], marker, &rest[marker.len()..]));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// amount at the start of the text in its commodity's style, with the rest separated by a space,
+// or by two spaces before a comment
+fn styled_amount(
+    text: &str,
+    styles: &HashMap<Commodity, CommodityStyle>,
+    decimal_mark: Option<char>,
+) -> String {
+    let mut state = State {
+        decimal_mark,
+        ..State::default()
+    };
+    let parsed = raw_amount()
+        .then(any().repeated().to_slice())
+        .parse_with_state(text, &mut state)
+        .into_result();
+    let Ok((amount, rest)) = parsed else {
+        return text.to_string();
+    };
+    if decimal_mark.is_none() && is_ambiguous(&amount) {
+        return text.to_string();
+    }
+    let amount = match styles.get(&amount.commodity) {
+        Some(style) => Amount {
+            style: CommodityStyle {
+                // the amount's own precision, so that its value stays the same
+                precision: amount.style.precision,
+                ..style.clone()
+            },
+            ..amount
+        },
+        None => amount,
+    };
+    let amount = amount.style.format_with_decimal_mark(&amount, decimal_mark);
+    match rest.trim() {
+        "" => amount,
+        rest if rest.starts_with(';') => format!("{amount}  {rest}"),
+        rest => format!("{amount} {rest}"),
+    }
+}
+
+// i.e. "1,234", which is either a decimal or an integer with a digit group mark. written in another
+// style it would be read only one way, so it is kept as it is.
+fn is_ambiguous(amount: &Amount) -> bool {
+    amount.style.digit_group_mark.is_none()
+        && amount.style.decimal_mark.is_some()
+        && amount.quantity.places == 3
+        && amount.quantity.mantissa < 1_000_000
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions() {
+        let journal = "; journal
+commodity $1,000.00
+
+2024/1/5   *   (12) shop | groceries  ; note
+  ; about the shop
+ assets:bank:checking\t-1234.5 $
+   ! expenses:food      $1234.50  =  $1234.50  ; tag: x
+\texpenses:misc
+~ monthly  budget
+    (expenses:food)  $400
+";
+        assert_eq!(
+            format(journal).unwrap(),
+            "; journal
+commodity $1,000.00
+
+2024-01-05 * (12) shop | groceries  ; note
+    ; about the shop
+    assets:bank:checking  $-1,234.5
+    ! expenses:food       $1,234.50 = $1,234.50  ; tag: x
+    expenses:misc
+~ monthly  budget
+    (expenses:food)  $400
+"
+        );
+    }
+
+    #[test]
+    fn assertions() {
+        let journal = "commodity $1,000.00
+
+2024-01-05 shop
+    a  $1000.5==*$2000.5
+    b    =  $-1000.5  ; note
+    c  3 \"A=B\"  ; a=b
+";
+        assert_eq!(
+            format(journal).unwrap(),
+            "commodity $1,000.00
+
+2024-01-05 shop
+    a  $1,000.5 ==* $2,000.5
+    b  = $-1,000.5  ; note
+    c  3 \"A=B\"  ; a=b
+"
+        );
+    }
+
+    #[test]
+    fn formatted_is_unchanged() {
+        let journal = "2024-01-05 shop
+    assets:cash  -5 EUR @ $1.10
+    expenses:food
+";
+        assert_eq!(format(journal).unwrap(), journal);
+        assert_eq!(format(&format(journal).unwrap()).unwrap(), journal);
+    }
+
+    #[test]
+    fn comment_block() {
+        let journal = "comment
+2024/1/5   shop
+  a  $1
+end comment
+
+2024/1/5   shop
+  a  $1
+  b
+";
+        assert_eq!(
+            format(journal).unwrap(),
+            "comment
+2024/1/5   shop
+  a  $1
+end comment
+
+2024-01-05 shop
+    a  $1
+    b
+"
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        let journal = "; journal\r\n2024/1/5 shop\r\n  a  $1\r\n  b\n";
+        assert_eq!(
+            format(journal).unwrap(),
+            "; journal\r\n2024-01-05 shop\r\n    a  $1\r\n    b\n"
+        );
+    }
+
+    #[test]
+    fn ambiguous_amounts() {
+        let journal = "commodity $1,000.00
+
+2024-01-05 shop
+    a  $1,234
+    b  $1.234,5
+    c
+";
+        assert_eq!(
+            format(journal).unwrap(),
+            "commodity $1,000.00
+
+2024-01-05 shop
+    a  $1,234
+    b  $1,234.5
+    c
+"
+        );
+    }
+
+    #[test]
+    fn declared_decimal_mark() {
+        let journal = "commodity 1.000,00 EUR
+decimal-mark ,
+
+2024-01-05 shop
+    a  1,234 EUR
+    b  1234 EUR = 2.468 EUR
+    c
+";
+        assert_eq!(
+            format(journal).unwrap(),
+            "commodity 1.000,00 EUR
+decimal-mark ,
+
+2024-01-05 shop
+    a  1,234 EUR
+    b  1.234 EUR = 2.468 EUR
+    c
+"
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(
+            normalized_date("2024.3.9=4/1"),
+            Some(String::from("2024-03-09=04-01"))
+        );
+        assert_eq!(normalized_date("shop"), None);
+    }
+
+    fn transaction() -> impl proptest::strategy::Strategy<Value = String> {
+        use proptest::prelude::*;
+
+        let date = (
+            2000..2030,
+            1..=12,
+            1..=28,
+            proptest::sample::select(vec!['-', '/', '.']),
+        )
+            .prop_map(|(year, month, day, separator)| {
+                format!("{year}{separator}{month}{separator}{day}")
+            });
+        let amount = proptest::sample::select(vec![
+            "",
+            "$1",
+            "-5 EUR",
+            "1.000,50 EUR",
+            "$ -1,234.5",
+            "10 AAPL @ $1.5",
+            "3 \"A B\" = 3 \"A B\"",
+        ]);
+        let posting = (
+            proptest::sample::select(vec![" ", "    ", "\t", "  ! "]),
+            "[a-z]{1,6}(:[a-z]{1,6}){0,2}",
+            amount,
+        )
+            .prop_map(|(indent, account, amount)| {
+                if amount.is_empty() {
+                    format!("{indent}{account}\n")
+                } else {
+                    format!("{indent}{account}  {amount}\n")
+                }
+            });
+        (
+            date,
+            proptest::sample::select(vec!["", " *", "   !"]),
+            "[a-z]{1,10}",
+            proptest::collection::vec(posting, 1..4),
+        )
+            .prop_map(|(date, status, payee, postings)| {
+                format!("{date}{status} {payee}\n{}", postings.concat())
+            })
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn formatted_parses_and_is_stable(
+            transactions in proptest::collection::vec(transaction(), 0..5)
+        ) {
+            let journal = transactions.concat();
+            let formatted = format(&journal).unwrap();
+            proptest::prop_assert_eq!(
+                crate::parse(&formatted).unwrap(),
+                crate::parse(&journal).unwrap(),
+                "{}",
+                formatted
+            );
+            proptest::prop_assert_eq!(format(&formatted).unwrap(), formatted);
+        }
+    }
+}
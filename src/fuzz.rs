@@ -0,0 +1,36 @@
+use chumsky::prelude::*;
+
+use crate::component::{
+    account_name::account_name, amount::amount, comment::block, commodity::commodity, date,
+    lot::lot, period::interval::interval, period::period, price::price, quantity::quantity,
+    time::time,
+};
+use crate::state::State;
+
+// runs a parser over the whole input, discarding the result
+pub type Run = fn(&str);
+
+// component parsers by name, for the fuzz targets. they are not public otherwise.
+pub const COMPONENTS: [(&str, Run); 12] = [
+    ("account_name", |input| run(account_name::<String>(), input)),
+    ("amount", |input| run(amount::<String>(), input)),
+    ("block", |input| run(block::<String>(), input)),
+    ("commodity", |input| run(commodity::<String>(), input)),
+    ("interval", |input| run(interval(), input)),
+    ("lot", |input| run(lot::<String>(), input)),
+    ("period", |input| run(period(), input)),
+    ("price", |input| run(price::<String>(), input)),
+    ("quantity", |input| run(quantity(), input)),
+    ("simple_date", |input| run(date::simple::date(), input)),
+    ("smart_date", |input| run(date::smart::date(), input)),
+    ("time", |input| run(time(), input)),
+];
+
+fn run<'a, T>(
+    parser: impl Parser<'a, &'a str, T, extra::Full<Rich<'a, char>, State, ()>>,
+    input: &'a str,
+) {
+    let _ = parser
+        .then_ignore(end())
+        .parse_with_state(input, &mut State::default());
+}
@@ -0,0 +1,279 @@
+use std::ops::Range;
+
+use chumsky::span::SimpleSpan;
+
+use crate::chunk::{chunks, parse_chunk, ParseError};
+use crate::directive::Directive;
+use crate::state::State;
+
+// replaces the text in the byte range
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+struct Chunk {
+    range: Range<usize>,
+    // parser state before the chunk, from the directives above it
+    state: State,
+    directives: Vec<Directive>,
+    errors: Vec<ParseError>,
+}
+
+// journal text with its directives, parsed chunk by chunk so that an edit only reparses the
+// chunks it touches, and the chunks below them whose parser state changed
+pub struct Document {
+    text: String,
+    chunks: Vec<Chunk>,
+}
+
+impl Document {
+    #[must_use]
+    pub fn new(text: String) -> Self {
+        let mut document = Self {
+            text,
+            chunks: Vec::new(),
+        };
+        let ranges = chunks(&document.text);
+        document.chunks = parse_chunks(&document.text, ranges, &mut State::default());
+        document
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn directives(&self) -> impl Iterator<Item = &Directive> {
+        self.chunks.iter().flat_map(|chunk| &chunk.directives)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ParseError> {
+        self.chunks.iter().flat_map(|chunk| &chunk.errors)
+    }
+
+    // applies the edit and returns the number of reparsed chunks. the range is clamped to the
+    // text and widened to character boundaries.
+    pub fn edit(&mut self, edit: &Edit) -> usize {
+        let mut start = edit.range.start.min(self.text.len());
+        let mut end = edit.range.end.clamp(start, self.text.len());
+        while !self.text.is_char_boundary(start) {
+            start -= 1;
+        }
+        while !self.text.is_char_boundary(end) {
+            end += 1;
+        }
+        let removes_block_line = has_block_line(lines_around(&self.text, start..end));
+        self.text.replace_range(start..end, &edit.text);
+        let inserted = start..start + edit.text.len();
+        let touches_block =
+            removes_block_line || has_block_line(lines_around(&self.text, inserted));
+
+        if self.chunks.is_empty() {
+            self.chunks = parse_chunks(&self.text, chunks(&self.text), &mut State::default());
+            return self.chunks.len();
+        }
+        // the chunk before the edit may take over lines indented by it
+        let first = self.index(start.saturating_sub(1));
+        let region_start = self.chunks[first].range.start;
+        let region = |last: usize| {
+            let region_end = self.chunks[last].range.end - end + start + edit.text.len();
+            chunks(&self.text[region_start..region_end])
+                .into_iter()
+                .map(|range| range.start + region_start..range.end + region_start)
+                .collect::<Vec<_>>()
+        };
+        // comment blocks are not part of the state, so when one may start or end elsewhere the
+        // rest of the text is chunked again
+        let mut last = if touches_block {
+            self.chunks.len() - 1
+        } else {
+            self.index(end)
+        };
+        let mut ranges = region(last);
+        if ends_in_open_block(&self.text, &ranges) {
+            last = self.chunks.len() - 1;
+            ranges = region(last);
+        }
+        let mut state = self.chunks[first].state.clone();
+        let mut reparsed = parse_chunks(&self.text, ranges, &mut state);
+
+        let mut rest = self.chunks.split_off(last + 1);
+        self.chunks.truncate(first);
+        for chunk in &mut rest {
+            shift(chunk, start, end, edit.text.len());
+        }
+        // chunks below are reparsed until the state before them is the same as before the edit
+        let mut rest = rest.into_iter().peekable();
+        while let Some(chunk) = rest.next_if(|chunk| chunk.state != state) {
+            reparsed.extend(parse_chunks(&self.text, vec![chunk.range], &mut state));
+        }
+        let count = reparsed.len();
+        self.chunks.extend(reparsed);
+        self.chunks.extend(rest);
+        count
+    }
+
+    // index of the chunk containing the offset, or the last chunk at the end of the text
+    fn index(&self, offset: usize) -> usize {
+        self.chunks
+            .partition_point(|chunk| chunk.range.end <= offset)
+            .min(self.chunks.len() - 1)
+    }
+}
+
+// parses the chunks in order, leaving the state as it is after the last one
+fn parse_chunks(text: &str, ranges: Vec<Range<usize>>, state: &mut State) -> Vec<Chunk> {
+    ranges
+        .into_iter()
+        .map(|range| {
+            let before = state.clone();
+            let (directives, errors) = parse_chunk(&text[range.clone()], range.start, state);
+            Chunk {
+                range,
+                state: before,
+                directives,
+                errors,
+            }
+        })
+        .collect()
+}
+
+// whole lines of the text that the byte range is on
+fn lines_around(text: &str, range: Range<usize>) -> &str {
+    let start = text[..range.start].rfind('\n').map_or(0, |index| index + 1);
+    let end = text[range.end..]
+        .find('\n')
+        .map_or(text.len(), |index| range.end + index);
+    &text[start..end]
+}
+
+fn has_block_line(text: &str) -> bool {
+    text.lines()
+        .any(|line| matches!(line.trim_end(), "comment" | "end comment"))
+}
+
+// whether the last chunk is a comment block without its end, which would take in the chunks after
+fn ends_in_open_block(text: &str, ranges: &[Range<usize>]) -> bool {
+    ranges.last().is_some_and(|range| {
+        let mut lines = text[range.clone()].lines().map(str::trim_end);
+        lines.next() == Some("comment") && lines.next_back() != Some("end comment")
+    })
+}
+
+// moves a chunk after the edit by the change in length
+fn shift(chunk: &mut Chunk, start: usize, end: usize, inserted: usize) {
+    let offset = |position: usize| position - (end - start) + inserted;
+    chunk.range = offset(chunk.range.start)..offset(chunk.range.end);
+    for error in &mut chunk.errors {
+        error.span = SimpleSpan::new(offset(error.span.start), offset(error.span.end));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOURNAL: &str = "Y2023
+01-05 shop
+    expenses:food  $10
+    assets:cash
+01-06 rent
+    expenses:rent  $500
+    assets:bank
+";
+
+    fn dates(document: &Document) -> Vec<String> {
+        document
+            .directives()
+            .filter_map(|directive| match directive {
+                Directive::Transaction(transaction) => Some(transaction.date.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn edit(document: &mut Document, find: &str, text: &str) -> usize {
+        let start = document.text().find(find).unwrap();
+        document.edit(&Edit {
+            range: start..start + find.len(),
+            text: text.to_string(),
+        })
+    }
+
+    #[test]
+    fn reparse_touched_chunk() {
+        let mut document = Document::new(JOURNAL.to_string());
+        assert_eq!(dates(&document), vec!["2023-01-05", "2023-01-06"]);
+        assert_eq!(edit(&mut document, "06 rent", "07 rent"), 1);
+        assert_eq!(dates(&document), vec!["2023-01-05", "2023-01-07"]);
+        assert_eq!(document.errors().count(), 0);
+        assert_eq!(
+            crate::parse(document.text()).unwrap().len(),
+            document.directives().count()
+        );
+    }
+
+    #[test]
+    fn reparse_chunks_after_state_change() {
+        let mut document = Document::new(JOURNAL.to_string());
+        assert_eq!(edit(&mut document, "2023", "2024"), 3);
+        assert_eq!(dates(&document), vec!["2024-01-05", "2024-01-06"]);
+    }
+
+    #[test]
+    fn errors_move_with_text() {
+        let mut document = Document::new(JOURNAL.to_string());
+        edit(&mut document, "    assets:bank", "    assets:bank  $x");
+        let error = document.errors().next().unwrap().span.start;
+        edit(&mut document, "shop", "grocery shop");
+        assert_eq!(document.errors().next().unwrap().span.start, error + 8);
+
+        // indented lines join the chunk above
+        edit(&mut document, "$x", "");
+        edit(
+            &mut document,
+            "01-06 rent",
+            "    assets:cash  $1\n01-06 rent",
+        );
+        assert_eq!(document.errors().count(), 0);
+        let Some(Directive::Transaction(transaction)) = document.directives().nth(1) else {
+            panic!("expected transaction");
+        };
+        assert_eq!(transaction.postings.len(), 3);
+    }
+
+    fn same_as_full_parse(document: &Document) {
+        assert_eq!(
+            format!("{:?}", document.directives().collect::<Vec<_>>()),
+            format!("{:?}", crate::parse(document.text()).unwrap())
+        );
+    }
+
+    #[test]
+    fn comment_blocks() {
+        let journal = format!(
+            "{JOURNAL}comment\nnote\nend comment\n\n2023-02-01 gym\n    expenses:gym  $20\n    assets:cash\n"
+        );
+        let mut document = Document::new(journal);
+        assert_eq!(
+            dates(&document),
+            vec!["2023-01-05", "2023-01-06", "2023-02-01"]
+        );
+
+        // the block runs over the transaction up to the end of the block after it
+        edit(&mut document, "01-06 rent", "comment\n01-06 rent");
+        assert_eq!(document.errors().count(), 0);
+        same_as_full_parse(&document);
+        assert_eq!(dates(&document), vec!["2023-01-05", "2023-02-01"]);
+
+        edit(&mut document, "comment\n01-06", "01-06");
+        assert_eq!(document.errors().count(), 0);
+        same_as_full_parse(&document);
+        assert_eq!(
+            dates(&document),
+            vec!["2023-01-05", "2023-01-06", "2023-02-01"]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use rust_decimal::Decimal;
+
+use crate::component::account_name::AccountName;
+use crate::component::commodity::Commodity;
+use crate::directive::Directive;
+use crate::mixed_amount::Overflow;
+use crate::report::entries;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountId(u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CommodityId(u32);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PayeeId(u32);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Posting {
+    pub account: AccountId,
+    pub is_virtual: bool,
+    // amount in each commodity, inferred for a posting without amount
+    pub amounts: Vec<(CommodityId, Decimal)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction {
+    pub date: chrono::NaiveDate,
+    pub payee: PayeeId,
+    pub postings: Vec<Posting>,
+}
+
+// values stored once and referred to by their index
+#[derive(Clone, Debug)]
+struct Interner<T> {
+    values: Vec<T>,
+    ids: HashMap<T, u32>,
+}
+
+impl<T> Default for Interner<T> {
+    fn default() -> Self {
+        Self {
+            values: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+
+// values are looked up by a borrowed form, i.e. a String by &str, and only copied when they are
+// interned for the first time
+impl<T: Clone + Eq + Hash> Interner<T> {
+    fn intern<Q>(&mut self, value: &Q) -> u32
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash + ToOwned<Owned = T>,
+    {
+        if let Some(id) = self.ids.get(value) {
+            return *id;
+        }
+        let id = u32::try_from(self.values.len()).expect("less than 2^32 values");
+        let value = value.to_owned();
+        self.values.push(value.clone());
+        self.ids.insert(value, id);
+        id
+    }
+
+    fn get<Q>(&self, value: &Q) -> Option<u32>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Eq + Hash,
+    {
+        self.ids.get(value).copied()
+    }
+
+    fn resolve(&self, id: u32) -> &T {
+        &self.values[id as usize]
+    }
+}
+
+// directives with their account names, commodities and payees interned, so that postings refer
+// to them by ids that are cheap to copy and compare
+#[derive(Clone, Debug, Default)]
+pub struct Journal {
+    directives: Vec<Directive>,
+    segments: Interner<String>,
+    // accounts as interned segments. parents of an account are interned before it.
+    accounts: Interner<Vec<u32>>,
+    commodities: Interner<Commodity>,
+    payees: Interner<String>,
+    transactions: Vec<Transaction>,
+}
+
+impl Journal {
+    #[allow(clippy::missing_errors_doc)]
+    pub fn new(directives: Vec<Directive>) -> Result<Self, Overflow> {
+        let mut journal = Self::default();
+        for directive in &directives {
+            if let Directive::Account(account) = directive {
+                journal.intern_account(&account.account_name);
+            }
+        }
+        let mut entries = entries(&directives)?.into_iter().peekable();
+        while let Some(first) = entries.next() {
+            let transaction = first.transaction;
+            let mut postings = Vec::with_capacity(transaction.postings.len());
+            for entry in std::iter::once(first).chain(std::iter::from_fn(|| {
+                entries.next_if(|entry| std::ptr::eq(entry.transaction, transaction))
+            })) {
+                let amounts = entry
+                    .amount
+                    .amounts()
+                    .map(|amount| {
+                        let commodity = CommodityId(journal.commodities.intern(&amount.commodity));
+                        (commodity, amount.to_decimal())
+                    })
+                    .collect();
+                postings.push(Posting {
+                    account: journal.intern_account(&entry.posting.account_name),
+                    is_virtual: entry.posting.is_virtual,
+                    amounts,
+                });
+            }
+            journal.transactions.push(Transaction {
+                date: transaction.date,
+                payee: PayeeId(journal.payees.intern(transaction.payee.as_str())),
+                postings,
+            });
+        }
+        journal.directives = directives;
+        Ok(journal)
+    }
+
+    fn intern_account(&mut self, account_name: &AccountName) -> AccountId {
+        let segments = account_name
+            .0
+            .iter()
+            .map(|segment| self.segments.intern(segment.as_str()))
+            .collect::<Vec<_>>();
+        if let Some(id) = self.accounts.get(segments.as_slice()) {
+            return AccountId(id);
+        }
+        for depth in 1..segments.len() {
+            self.accounts.intern(&segments[..depth]);
+        }
+        AccountId(self.accounts.intern(segments.as_slice()))
+    }
+
+    #[must_use]
+    pub fn directives(&self) -> &[Directive] {
+        &self.directives
+    }
+
+    #[must_use]
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    // declared and used accounts with their parents, parents first
+    pub fn accounts(&self) -> impl Iterator<Item = AccountId> {
+        (0..u32::try_from(self.accounts.values.len()).unwrap_or(u32::MAX)).map(AccountId)
+    }
+
+    #[must_use]
+    pub fn account_id(&self, account_name: &AccountName) -> Option<AccountId> {
+        let segments = account_name
+            .0
+            .iter()
+            .map(|segment| self.segments.get(segment.as_str()))
+            .collect::<Option<Vec<_>>>()?;
+        self.accounts.get(segments.as_slice()).map(AccountId)
+    }
+
+    #[must_use]
+    pub fn account_name(&self, id: AccountId) -> AccountName {
+        AccountName(
+            self.accounts
+                .resolve(id.0)
+                .iter()
+                .map(|segment| self.segments.resolve(*segment).clone())
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn parent(&self, id: AccountId) -> Option<AccountId> {
+        let segments = self.accounts.resolve(id.0);
+        let parent = &segments[..segments.len().checked_sub(1).filter(|len| *len > 0)?];
+        self.accounts.get(parent).map(AccountId)
+    }
+
+    #[must_use]
+    pub fn commodity_id(&self, commodity: &Commodity) -> Option<CommodityId> {
+        self.commodities.get(commodity).map(CommodityId)
+    }
+
+    #[must_use]
+    pub fn commodity(&self, id: CommodityId) -> &Commodity {
+        self.commodities.resolve(id.0)
+    }
+
+    #[must_use]
+    pub fn payee_id(&self, payee: &str) -> Option<PayeeId> {
+        self.payees.get(payee).map(PayeeId)
+    }
+
+    #[must_use]
+    pub fn payee(&self, id: PayeeId) -> &str {
+        self.payees.resolve(id.0)
+    }
+
+    // sum of the postings to each account in each commodity, without subaccounts
+    #[allow(clippy::missing_errors_doc)]
+    pub fn balances(&self) -> Result<HashMap<(AccountId, CommodityId), Decimal>, Overflow> {
+        let mut balances = HashMap::new();
+        for posting in self.transactions.iter().flat_map(|t| &t.postings) {
+            for (commodity, quantity) in &posting.amounts {
+                let balance = balances
+                    .entry((posting.account, *commodity))
+                    .or_insert(Decimal::ZERO);
+                *balance = balance.checked_add(*quantity).ok_or(Overflow)?;
+            }
+        }
+        Ok(balances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_name(name: &str) -> AccountName {
+        AccountName(name.split(':').map(String::from).collect())
+    }
+
+    #[test]
+    fn interned() {
+        let journal = Journal::new(
+            crate::parse(
+                "account assets:bank
+2024-01-01 shop
+    expenses:food  $10
+    assets:bank
+2024-01-02 shop
+    expenses:food  $5
+    assets:bank  $-5
+",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let [first, second] = journal.transactions() else {
+            panic!("expected two transactions");
+        };
+        assert_eq!(first.payee, second.payee);
+        assert_eq!(journal.payee(first.payee), "shop");
+        assert_eq!(journal.payee_id("shop"), Some(first.payee));
+        assert_eq!(journal.payee_id("market"), None);
+        assert_eq!(first.postings[0].account, second.postings[0].account);
+        assert_eq!(
+            journal.account_name(first.postings[0].account),
+            account_name("expenses:food")
+        );
+        assert_eq!(journal.accounts().count(), 4);
+
+        let bank = journal.account_id(&account_name("assets:bank")).unwrap();
+        let assets = journal.account_id(&account_name("assets")).unwrap();
+        assert_eq!(journal.parent(bank), Some(assets));
+        assert_eq!(journal.parent(assets), None);
+        assert_eq!(journal.account_id(&account_name("assets:cash")), None);
+
+        let dollar = journal.commodity_id(&Commodity::from_str("$")).unwrap();
+        assert_eq!(journal.commodity(dollar), &Commodity::from_str("$"));
+        assert_eq!(
+            journal.balances().unwrap().get(&(bank, dollar)),
+            Some(&Decimal::from(-15))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let directives = crate::parse(
+            "2024-01-01
+    assets:cash  18446744073709551615 EUR
+    assets:bank  1 EUR
+    equity
+",
+        )
+        .unwrap();
+        assert!(matches!(Journal::new(directives), Err(Overflow)));
+    }
+}
@@ -1,19 +1,167 @@
+// combined parser types nest deeper than the default limit
+#![recursion_limit = "256"]
+
 use chumsky::prelude::*;
 
 use self::{
-    directive::{directives, Directive},
+    directive::{directives, spanned_directives},
     state::State,
 };
 
+pub use self::{
+    check::{check, Check, Diagnostic},
+    chunk::ParseError,
+    component::account_name::AccountName,
+    component::amount::{
+        style::{CommodityStyle, Side},
+        Amount,
+    },
+    component::commodity::Commodity,
+    component::lot::Lot,
+    component::period::interval::Interval,
+    conversion::{cost, value, value_mixed, Conversion},
+    directive::{account::AccountType, alias::Alias, transaction::status::Status, Directive},
+    format::format,
+    incremental::{Document, Edit},
+    mixed_amount::{MixedAmount, Overflow},
+    parallel::parse_parallel,
+    price_db::PriceDb,
+    query::Query,
+    stream::parse_reader,
+    styles::commodity_styles,
+    text::Text,
+};
+pub use rust_decimal::Decimal;
+
+pub mod account_tree;
+pub mod borrowed;
+mod check;
+mod chunk;
 mod component;
+mod conversion;
 mod directive;
+pub mod duplicates;
+mod format;
+#[cfg(fuzzing)]
+pub mod fuzz;
+mod incremental;
+pub mod journal;
+mod mixed_amount;
+mod parallel;
+mod price_db;
+mod query;
+pub mod report;
 mod state;
+pub mod stream;
+mod styles;
+mod text;
 mod utils;
 
 #[allow(clippy::missing_errors_doc)]
-pub fn parse(contents: &str) -> Result<Vec<Directive>, Vec<Rich<char, SimpleSpan>>> {
-    directives()
+pub fn parse(contents: &str) -> Result<Vec<Directive>, Vec<Rich<'_, char, SimpleSpan>>> {
+    parse_with_aliases(contents, &[])
+}
+
+// directives with the span of their text, for diagnostics like the ones from check
+#[allow(clippy::missing_errors_doc, clippy::type_complexity)]
+pub fn parse_spanned(
+    contents: &str,
+) -> Result<Vec<(Directive, SimpleSpan)>, Vec<Rich<'_, char, SimpleSpan>>> {
+    spanned_directives()
         .then_ignore(end())
         .parse_with_state(contents, &mut State::default())
         .into_result()
 }
+
+// aliases are applied to every account name after the journal's own alias directives, like
+// hledger's --alias option
+#[allow(clippy::missing_errors_doc)]
+pub fn parse_with_aliases<'a>(
+    contents: &'a str,
+    aliases: &[Alias],
+) -> Result<Vec<Directive>, Vec<Rich<'a, char, SimpleSpan>>> {
+    let mut state = State {
+        option_aliases: aliases.to_vec(),
+        ..State::default()
+    };
+    directives()
+        .then_ignore(end())
+        .parse_with_state(contents, &mut state)
+        .into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    // pieces of journal syntax, so that arbitrary input gets past the first characters
+    const TOKENS: [&str; 48] = [
+        "2024-01-05",
+        "2024/02/30",
+        "02-29",
+        "1.5",
+        "Y",
+        "year ",
+        "2024",
+        "99999999999999999999",
+        " ",
+        "  ",
+        "\t",
+        "\n",
+        "    ",
+        "$",
+        "€",
+        "EUR",
+        "\"A B\"",
+        "-",
+        "+",
+        "1,000.00",
+        "1.000,5",
+        ".",
+        "@",
+        "@@",
+        "=",
+        "==",
+        "{",
+        "}",
+        "(",
+        ")",
+        "[",
+        "]",
+        ";",
+        "#",
+        "*",
+        "!",
+        "|",
+        ":",
+        "account ",
+        "commodity ",
+        "P ",
+        "D ",
+        "~ ",
+        "alias ",
+        "apply account ",
+        "end ",
+        "comment",
+        "every 99999999999 days",
+    ];
+
+    proptest! {
+        #[test]
+        fn parse_never_panics(contents in "\\PC*") {
+            let _ = parse(&contents);
+        }
+
+        #[test]
+        fn parse_tokens_never_panics(
+            tokens in proptest::collection::vec(proptest::sample::select(TOKENS.to_vec()), 0..40)
+        ) {
+            let contents = tokens.concat();
+            let _ = parse(&contents);
+            let _ = parse_parallel(&contents);
+            let _ = format(&contents);
+        }
+    }
+}
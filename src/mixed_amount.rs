@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use rust_decimal::Decimal;
+
+use crate::component::amount::Amount;
+use crate::component::commodity::Commodity;
+
+// sum of amounts in several commodities
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MixedAmount(BTreeMap<Commodity, Amount>);
+
+// sum or product of amounts that does not fit in a decimal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl std::fmt::Display for Overflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("amount is too large")
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+impl MixedAmount {
+    #[must_use]
+    pub fn from_amount(amount: &Amount) -> Self {
+        let mut mixed = Self::default();
+        if !amount.to_decimal().is_zero() {
+            mixed.0.insert(amount.commodity.clone(), amount.clone());
+        }
+        mixed
+    }
+
+    // commodities that sum up to zero are dropped
+    #[allow(clippy::missing_errors_doc)]
+    pub fn add(&mut self, amount: &Amount) -> Result<(), Overflow> {
+        let sum = match self.0.get(&amount.commodity) {
+            Some(existing) => {
+                let mut style = existing.style.clone();
+                style.precision = style.precision.max(amount.style.precision);
+                existing
+                    .to_decimal()
+                    .checked_add(amount.to_decimal())
+                    .and_then(|sum| {
+                        Amount::checked_from_decimal(sum, amount.commodity.clone(), style)
+                    })
+                    .ok_or(Overflow)?
+            }
+            None => amount.clone(),
+        };
+        if sum.to_decimal().is_zero() {
+            self.0.remove(&amount.commodity);
+        } else {
+            self.0.insert(amount.commodity.clone(), sum);
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::missing_errors_doc)]
+    pub fn add_mixed(&mut self, other: &MixedAmount) -> Result<(), Overflow> {
+        for amount in other.0.values() {
+            self.add(amount)?;
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn negated(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|(commodity, amount)| {
+                    let negated = Amount::from_decimal(
+                        -amount.to_decimal(),
+                        commodity.clone(),
+                        amount.style.clone(),
+                    );
+                    (commodity.clone(), negated)
+                })
+                .collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, commodity: &Commodity) -> Decimal {
+        self.0
+            .get(commodity)
+            .map_or(Decimal::ZERO, Amount::to_decimal)
+    }
+
+    // non-zero amounts, ordered by commodity
+    pub fn amounts(&self) -> impl Iterator<Item = &Amount> {
+        self.0.values()
+    }
+}
+
+impl std::fmt::Display for MixedAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("0");
+        }
+        let amounts = self.0.values().map(ToString::to_string).collect::<Vec<_>>();
+        f.write_str(&amounts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chumsky::prelude::*;
+
+    use crate::component::amount::amount;
+
+    use super::*;
+
+    fn parse(input: &str) -> Amount {
+        amount()
+            .then_ignore(end())
+            .parse(input)
+            .into_result()
+            .unwrap()
+    }
+
+    #[test]
+    fn sum() {
+        let mut mixed = MixedAmount::from_amount(&parse("$1.5"));
+        mixed.add(&parse("10 EUR")).unwrap();
+        mixed.add(&parse("$-0.25")).unwrap();
+        assert_eq!(mixed.to_string(), "$1.25, 10 EUR");
+        assert_eq!(mixed.negated().to_string(), "$-1.25, -10 EUR");
+
+        mixed.add_mixed(&mixed.negated()).unwrap();
+        assert!(mixed.is_zero());
+        assert_eq!(mixed.to_string(), "0");
+    }
+
+    #[test]
+    fn overflow() {
+        let max = parse("18446744073709551615 EUR");
+        let mut mixed = MixedAmount::from_amount(&max);
+        assert_eq!(mixed.add(&max), Err(Overflow));
+        assert_eq!(mixed, MixedAmount::from_amount(&max));
+    }
+}
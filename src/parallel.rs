@@ -0,0 +1,134 @@
+use std::num::NonZeroUsize;
+use std::ops::Range;
+
+use crate::chunk::{chunks, parse_chunk, ParseError};
+use crate::directive::Directive;
+use crate::state::State;
+
+enum Chunk {
+    Parsed(Vec<Directive>, Vec<ParseError>),
+    // range of the chunk with the parser state before it
+    Pending(Range<usize>, State),
+}
+
+// same as parse, with the chunks between directives that change the parser state parsed on
+// all available threads
+#[allow(clippy::missing_errors_doc)]
+pub fn parse_parallel(contents: &str) -> Result<Vec<Directive>, Vec<ParseError>> {
+    // directives that change the state are parsed first, in order, to know the state before
+    // every other chunk
+    let mut state = State::default();
+    let mut parsed = chunks(contents)
+        .into_iter()
+        .map(|range| {
+            if changes_state(&contents[range.clone()]) {
+                let (directives, errors) =
+                    parse_chunk(&contents[range.clone()], range.start, &mut state);
+                Chunk::Parsed(directives, errors)
+            } else {
+                Chunk::Pending(range, state.clone())
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let threads = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let size = parsed.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        for group in parsed.chunks_mut(size) {
+            scope.spawn(|| {
+                for chunk in group {
+                    if let Chunk::Pending(range, state) = chunk {
+                        let (directives, errors) =
+                            parse_chunk(&contents[range.clone()], range.start, state);
+                        *chunk = Chunk::Parsed(directives, errors);
+                    }
+                }
+            });
+        }
+    });
+
+    let mut directives = Vec::new();
+    let mut errors = Vec::new();
+    for chunk in parsed {
+        if let Chunk::Parsed(chunk_directives, chunk_errors) = chunk {
+            directives.extend(chunk_directives);
+            errors.extend(chunk_errors);
+        }
+    }
+    if errors.is_empty() {
+        Ok(directives)
+    } else {
+        Err(errors)
+    }
+}
+
+// whether the chunk may hold a directive that changes the parser state: year, apply account,
+// alias, default commodity or decimal mark. other directives start differently.
+fn changes_state(chunk: &str) -> bool {
+    chunk.starts_with(['Y', 'D'])
+        || ["year", "apply", "end", "alias", "decimal-mark"]
+            .iter()
+            .any(|keyword| chunk.starts_with(keyword))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn same_as_parse() {
+        let mut journal = String::from("Y2023\nalias food = expenses:food\nD $1,000.00\n");
+        for day in 1..=28 {
+            writeln!(journal, "01-{day} shop\n    food  10\n    assets:cash").unwrap();
+            if day == 10 {
+                journal.push_str("apply account business\nyear 2024\nbucket assets:bank\n");
+            }
+            if day == 20 {
+                journal.push_str("end apply account\nend aliases\n");
+            }
+        }
+        assert_eq!(
+            format!("{:?}", parse_parallel(&journal).unwrap()),
+            format!("{:?}", crate::parse(&journal).unwrap())
+        );
+    }
+
+    #[test]
+    fn decimal_mark() {
+        let mut journal = String::from("2024-01-01 shop\n    a  1,500 EUR\n    b\n");
+        journal.push_str("decimal-mark ,\n");
+        for day in 2..=28 {
+            writeln!(
+                journal,
+                "2024-01-{day} shop\n    a  1.500 EUR\n    b  -1,500 EUR"
+            )
+            .unwrap();
+        }
+        let directives = parse_parallel(&journal).unwrap();
+        assert_eq!(
+            format!("{directives:?}"),
+            format!("{:?}", crate::parse(&journal).unwrap())
+        );
+        let Directive::Transaction(last) = directives.last().unwrap() else {
+            panic!("expected transaction");
+        };
+        let amount = |index: usize| last.postings[index].amount.as_ref().unwrap().to_decimal();
+        assert_eq!(amount(0), rust_decimal::Decimal::from(1500));
+        assert_eq!(amount(1), rust_decimal::Decimal::new(-15, 1));
+    }
+
+    #[test]
+    fn errors_in_order() {
+        let journal = "2024-01-05 shop\n    a  $x\n2024-01-06 shop\n    a  $y\n";
+        let errors = parse_parallel(journal).unwrap_err();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.span.start)
+                .collect::<Vec<_>>(),
+            vec![23, 49]
+        );
+    }
+}
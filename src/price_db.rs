@@ -0,0 +1,385 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use rust_decimal::Decimal;
+
+use crate::component::amount::{style::CommodityStyle, Amount};
+use crate::component::commodity::Commodity;
+use crate::component::price::Price as PostingPrice;
+use crate::directive::Directive;
+
+#[derive(Clone, Debug)]
+struct Rate {
+    date_time: chrono::NaiveDateTime,
+    // declared by a P directive rather than inferred from a posting price
+    is_declared: bool,
+    price: Decimal,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PriceDb {
+    // prices of one unit of the first commodity in the second, sorted by time. declared prices
+    // come after transaction prices at the same time, so they take precedence.
+    rates: HashMap<(Commodity, Commodity), Vec<Rate>>,
+    // commodities with a price in or of each commodity. ordered, so that of several equally short
+    // chains the same one is taken every time.
+    neighbours: BTreeMap<Commodity, BTreeSet<Commodity>>,
+    // style of each commodity as written in the first price using it
+    styles: HashMap<Commodity, CommodityStyle>,
+}
+
+impl PriceDb {
+    // collects P directives and @ / @@ posting prices
+    #[must_use]
+    pub fn from_directives(directives: &[Directive]) -> Self {
+        let mut db = Self::default();
+        for directive in directives {
+            match directive {
+                Directive::Price(price) => {
+                    db.insert(price.date_time(), &price.commodity, &price.amount, true);
+                }
+                Directive::Transaction(transaction) => {
+                    for posting in &transaction.postings {
+                        let (Some(amount), Some(price)) = (&posting.amount, &posting.price) else {
+                            continue;
+                        };
+                        let unit_price = match price {
+                            PostingPrice::Unit(price) => Some(price.clone()),
+                            PostingPrice::Total(price) => price
+                                .quantity
+                                .to_decimal()
+                                .checked_div(amount.quantity.to_decimal())
+                                .and_then(|rate| {
+                                    Amount::checked_from_decimal(
+                                        rate,
+                                        price.commodity.clone(),
+                                        price.style.clone(),
+                                    )
+                                }),
+                        };
+                        if let Some(unit_price) = unit_price {
+                            let date_time = transaction.date.and_time(chrono::NaiveTime::MIN);
+                            db.insert(date_time, &amount.commodity, &unit_price, false);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        db
+    }
+
+    fn insert(
+        &mut self,
+        date_time: chrono::NaiveDateTime,
+        commodity: &Commodity,
+        price: &Amount,
+        is_declared: bool,
+    ) {
+        if *commodity == price.commodity {
+            return;
+        }
+        self.styles
+            .entry(price.commodity.clone())
+            .or_insert_with(|| price.style.clone());
+        self.neighbours
+            .entry(commodity.clone())
+            .or_default()
+            .insert(price.commodity.clone());
+        self.neighbours
+            .entry(price.commodity.clone())
+            .or_default()
+            .insert(commodity.clone());
+        let rates = self
+            .rates
+            .entry((commodity.clone(), price.commodity.clone()))
+            .or_default();
+        let index = rates.partition_point(|other| {
+            (other.date_time, other.is_declared) <= (date_time, is_declared)
+        });
+        rates.insert(
+            index,
+            Rate {
+                date_time,
+                is_declared,
+                price: price.quantity.to_decimal(),
+            },
+        );
+    }
+
+    // price of one unit of `from` in `to` on the date, using the latest price on or before it.
+    // direct prices are preferred, then inverse prices, then chains through other commodities
+    // with the fewest steps.
+    #[must_use]
+    pub fn rate(
+        &self,
+        from: &Commodity,
+        to: &Commodity,
+        date: chrono::NaiveDate,
+    ) -> Option<Decimal> {
+        let end_of_day = date.and_hms_nano_opt(23, 59, 59, 1_999_999_999)?;
+        self.rate_at(from, to, end_of_day)
+    }
+
+    // same as rate, but ignores prices later in the day than the given time
+    #[must_use]
+    pub fn rate_at(
+        &self,
+        from: &Commodity,
+        to: &Commodity,
+        date_time: chrono::NaiveDateTime,
+    ) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        if let Some(rate) = self.direct_rate(from, to, date_time) {
+            return Some(rate);
+        }
+
+        let mut visited = HashSet::from([from]);
+        let mut queue = VecDeque::from([(from, Decimal::ONE)]);
+        while let Some((commodity, rate)) = queue.pop_front() {
+            for next in self.neighbours(commodity) {
+                if visited.contains(next) {
+                    continue;
+                }
+                let Some(rate) = self
+                    .direct_rate(commodity, next, date_time)
+                    .and_then(|step| rate.checked_mul(step))
+                else {
+                    continue;
+                };
+                // only once it is reached, since another path may have a price on the date
+                visited.insert(next);
+                if next == to {
+                    return Some(rate);
+                }
+                queue.push_back((next, rate));
+            }
+        }
+        None
+    }
+
+    // commodity of the latest direct price of `from` on or before the date
+    #[must_use]
+    pub fn latest_commodity(&self, from: &Commodity, date: chrono::NaiveDate) -> Option<Commodity> {
+        let end_of_day = date.and_hms_nano_opt(23, 59, 59, 1_999_999_999)?;
+        self.rates
+            .iter()
+            .filter(|((commodity, _), _)| commodity == from)
+            .filter_map(|((_, to), rates)| {
+                let index = rates.partition_point(|other| other.date_time <= end_of_day);
+                let rate = &rates[index.checked_sub(1)?];
+                Some(((rate.date_time, rate.is_declared), to))
+            })
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, to)| to.clone())
+    }
+
+    // amount converted to `to` at the rate on the date
+    #[must_use]
+    pub fn value(
+        &self,
+        amount: &Amount,
+        to: &Commodity,
+        date: chrono::NaiveDate,
+    ) -> Option<Amount> {
+        let value = self
+            .rate(&amount.commodity, to, date)?
+            .checked_mul(amount.to_decimal())?;
+        let style = if amount.commodity == *to {
+            amount.style.clone()
+        } else {
+            self.styles.get(to).cloned().unwrap_or_default()
+        };
+        Amount::checked_from_decimal(value, to.clone(), style)
+    }
+
+    fn direct_rate(
+        &self,
+        from: &Commodity,
+        to: &Commodity,
+        date_time: chrono::NaiveDateTime,
+    ) -> Option<Decimal> {
+        let latest = |from: &Commodity, to: &Commodity| {
+            let rates = self.rates.get(&(from.clone(), to.clone()))?;
+            let index = rates.partition_point(|other| other.date_time <= date_time);
+            index.checked_sub(1).map(|index| rates[index].price)
+        };
+        latest(from, to).or_else(|| {
+            latest(to, from)
+                .filter(|rate| !rate.is_zero())
+                .and_then(|rate| Decimal::ONE.checked_div(rate))
+        })
+    }
+
+    // commodities with a price in or of the given commodity
+    fn neighbours(&self, commodity: &Commodity) -> impl Iterator<Item = &Commodity> {
+        self.neighbours.get(commodity).into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn date(day: u32) -> chrono::NaiveDate {
+        chrono::NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    fn decimal(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn price_db() -> PriceDb {
+        let directives = crate::parse(
+            "P 2024-01-01 EUR $1.10
+P 2024-01-10 EUR $1.20
+P 2024-01-05 BTC 40000 EUR
+2024-01-03
+    assets:gold  10 gold @@ $500
+    assets:cash
+",
+        )
+        .unwrap();
+        PriceDb::from_directives(&directives)
+    }
+
+    #[test]
+    fn direct() {
+        let db = price_db();
+        let (eur, usd) = (Commodity::from_str("EUR"), Commodity::from_str("$"));
+        assert_eq!(db.rate(&eur, &usd, date(1)), Some(decimal("1.10")));
+        assert_eq!(db.rate(&eur, &usd, date(9)), Some(decimal("1.10")));
+        assert_eq!(db.rate(&eur, &usd, date(10)), Some(decimal("1.20")));
+        assert_eq!(db.rate(&eur, &eur, date(1)), Some(Decimal::ONE));
+        assert_eq!(
+            db.rate(
+                &eur,
+                &usd,
+                chrono::NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn from_transaction_price() {
+        let db = price_db();
+        let (gold, usd) = (Commodity::from_str("gold"), Commodity::from_str("$"));
+        assert_eq!(db.rate(&gold, &usd, date(3)), Some(decimal("50")));
+        assert_eq!(db.rate(&gold, &usd, date(2)), None);
+    }
+
+    #[test]
+    fn declared_over_transaction_price() {
+        let directives = crate::parse(
+            "P 2024-01-01 gold $45
+2024-01-01
+    assets:gold  1 gold @ $50
+    assets:cash
+",
+        )
+        .unwrap();
+        let db = PriceDb::from_directives(&directives);
+        let (gold, usd) = (Commodity::from_str("gold"), Commodity::from_str("$"));
+        assert_eq!(db.rate(&gold, &usd, date(1)), Some(decimal("45")));
+    }
+
+    #[test]
+    fn intraday() {
+        let directives = crate::parse(
+            "P 2024-01-01 12:00:00 BTC $42000
+P 2024-01-01 BTC $40000
+P 2024-01-01 18:30:00 BTC $43000
+P 2024-01-01 09:00:00 BTC $41000
+",
+        )
+        .unwrap();
+        let db = PriceDb::from_directives(&directives);
+        let (btc, usd) = (Commodity::from_str("BTC"), Commodity::from_str("$"));
+        let at = |hour| date(1).and_hms_opt(hour, 0, 0).unwrap();
+        assert_eq!(db.rate_at(&btc, &usd, at(8)), Some(decimal("40000")));
+        assert_eq!(db.rate_at(&btc, &usd, at(10)), Some(decimal("41000")));
+        assert_eq!(db.rate_at(&btc, &usd, at(18)), Some(decimal("42000")));
+        assert_eq!(db.rate(&btc, &usd, date(1)), Some(decimal("43000")));
+    }
+
+    #[test]
+    fn inverse() {
+        let db = price_db();
+        let (eur, usd) = (Commodity::from_str("EUR"), Commodity::from_str("$"));
+        assert_eq!(
+            db.rate(&usd, &eur, date(10)).map(|rate| rate.round_dp(4)),
+            Some(decimal("0.8333"))
+        );
+    }
+
+    #[test]
+    fn transitive() {
+        let db = price_db();
+        let (btc, usd, gold) = (
+            Commodity::from_str("BTC"),
+            Commodity::from_str("$"),
+            Commodity::from_str("gold"),
+        );
+        assert_eq!(db.rate(&btc, &usd, date(4)), None);
+        assert_eq!(db.rate(&btc, &usd, date(5)), Some(decimal("44000")));
+        assert_eq!(db.rate(&btc, &gold, date(10)), Some(decimal("960")));
+    }
+
+    #[test]
+    fn transitive_through_priced_path() {
+        let directives = crate::parse(
+            "P 2024-03-01 A 2 B
+P 2024-01-01 A 3 C
+P 2024-01-01 C 5 B
+P 2024-01-01 B 7 D
+",
+        )
+        .unwrap();
+        let db = PriceDb::from_directives(&directives);
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        assert_eq!(
+            db.rate(&Commodity::from_str("A"), &Commodity::from_str("D"), date),
+            Some(Decimal::from(105))
+        );
+    }
+
+    #[test]
+    fn equally_short_paths() {
+        // C is declared first, but B comes first in commodity order
+        let directives = crate::parse(
+            "P 2024-01-01 A 1 C
+P 2024-01-01 C 5 D
+P 2024-01-01 A 2 B
+P 2024-01-01 B 3 D
+",
+        )
+        .unwrap();
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        // every database has its own hash order
+        for _ in 0..20 {
+            let db = PriceDb::from_directives(&directives);
+            assert_eq!(
+                db.rate(&Commodity::from_str("A"), &Commodity::from_str("D"), date),
+                Some(Decimal::from(6))
+            );
+        }
+    }
+
+    #[test]
+    fn value() {
+        let db = price_db();
+        let amount = crate::parse("2024-01-01\n    a  -2 EUR\n    b\n").unwrap();
+        let Directive::Transaction(transaction) = &amount[0] else {
+            panic!("expected transaction");
+        };
+        let amount = transaction.postings[0].amount.as_ref().unwrap();
+        let value = db
+            .value(amount, &Commodity::from_str("$"), date(10))
+            .unwrap();
+        assert_eq!(value.to_string(), "$-2.40");
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::mem::discriminant;
+
+use chumsky::prelude::*;
+
+use crate::component::period::period;
+use crate::directive::transaction::status::Status;
+use crate::report::Entry;
+
+#[derive(Clone, Debug)]
+enum Term {
+    // acct:REGEX, or a bare REGEX
+    Account(regex::Regex),
+    // desc:REGEX, matched against "payee | description"
+    Description(regex::Regex),
+    // payee:REGEX
+    Payee(regex::Regex),
+    // cur:REGEX, matched against the whole commodity symbol
+    Commodity(regex::Regex),
+    // date:PERIOD, begin inclusive and end exclusive
+    Date(Option<chrono::NaiveDate>, Option<chrono::NaiveDate>),
+    // status:*, status:! or status: for unmarked
+    Status(Option<Status>),
+    // real:
+    Real,
+}
+
+// postings filter in hledger's query syntax, i.e. "expenses desc:shop not:status:! date:2024".
+// terms of the same kind match when any of them does, terms of different kinds must all match.
+#[derive(Clone, Debug, Default)]
+pub struct Query {
+    terms: Vec<Term>,
+    // not:TERM
+    negated: Vec<Term>,
+}
+
+impl std::str::FromStr for Query {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Self::default();
+        for word in s.split_whitespace() {
+            match word.strip_prefix("not:") {
+                Some(word) => query.negated.push(term(word)?),
+                None => query.terms.push(term(word)?),
+            }
+        }
+        Ok(query)
+    }
+}
+
+impl Query {
+    pub(crate) fn matches(&self, entry: &Entry) -> bool {
+        let mut kinds = HashMap::new();
+        for term in &self.terms {
+            *kinds.entry(discriminant(term)).or_insert(false) |= term.matches(entry);
+        }
+        kinds.values().all(|matches| *matches)
+            && !self.negated.iter().any(|term| term.matches(entry))
+    }
+}
+
+impl Term {
+    fn matches(&self, entry: &Entry) -> bool {
+        let transaction = entry.transaction;
+        match self {
+            Term::Account(regex) => regex.is_match(&entry.posting.account_name.0.join(":")),
+            Term::Description(regex) => regex.is_match(&match &transaction.description {
+                Some(description) => format!("{} | {description}", transaction.payee),
+                None => transaction.payee.clone(),
+            }),
+            Term::Payee(regex) => regex.is_match(&transaction.payee),
+            Term::Commodity(regex) => entry
+                .amount
+                .amounts()
+                .any(|amount| regex.is_match(&amount.commodity.to_string())),
+            Term::Date(begin, end) => {
+                begin.is_none_or(|begin| begin <= transaction.date)
+                    && end.is_none_or(|end| transaction.date < end)
+            }
+            Term::Status(status) => {
+                entry
+                    .posting
+                    .status
+                    .as_ref()
+                    .or(transaction.status.as_ref())
+                    == status.as_ref()
+            }
+            Term::Real => !entry.posting.is_virtual,
+        }
+    }
+}
+
+fn term(word: &str) -> Result<Term, String> {
+    let regex = |pattern: &str| {
+        regex::RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|error| error.to_string())
+    };
+    let Some((prefix, value)) = word.split_once(':') else {
+        return regex(word).map(Term::Account);
+    };
+    match prefix {
+        "acct" => regex(value).map(Term::Account),
+        "desc" => regex(value).map(Term::Description),
+        "payee" => regex(value).map(Term::Payee),
+        "cur" => regex(&format!("^(?:{value})$")).map(Term::Commodity),
+        "date" => period()
+            .then_ignore(end())
+            .parse(value)
+            .into_result()
+            .map_err(|_| format!("invalid period: {value}"))
+            .map(|period| Term::Date(period.begin, period.end)),
+        "status" => match value {
+            "*" => Ok(Term::Status(Some(Status::Cleared))),
+            "!" => Ok(Term::Status(Some(Status::Pending))),
+            "" => Ok(Term::Status(None)),
+            _ => Err(format!("invalid status: {value}")),
+        },
+        "real" => Ok(Term::Real),
+        // account names may contain colons
+        _ => regex(word).map(Term::Account),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::entries;
+
+    use super::*;
+
+    fn matching(query: &str) -> Vec<String> {
+        let directives = crate::parse(
+            "2024-01-05 * shop | groceries
+    expenses:food  $10
+    (budget:food)  $-10
+    assets:cash
+2024-02-10 landlord
+    expenses:rent  $500
+    ! assets:bank
+",
+        )
+        .unwrap();
+        let query = query.parse::<Query>().unwrap();
+        entries(&directives)
+            .unwrap()
+            .iter()
+            .filter(|entry| query.matches(entry))
+            .map(|entry| entry.posting.account_name.0.join(":"))
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(matching("").len(), 5);
+    }
+
+    #[test]
+    fn same_kind_or_different_kind_and() {
+        assert_eq!(
+            matching("food rent"),
+            vec!["expenses:food", "budget:food", "expenses:rent"]
+        );
+        assert_eq!(matching("food real:"), vec!["expenses:food"]);
+        assert_eq!(matching("acct:^e desc:groc"), vec!["expenses:food"]);
+    }
+
+    #[test]
+    fn status() {
+        assert_eq!(
+            matching("status:*"),
+            vec!["expenses:food", "budget:food", "assets:cash"]
+        );
+        assert_eq!(matching("status:!"), vec!["assets:bank"]);
+        assert_eq!(matching("status:"), vec!["expenses:rent"]);
+    }
+
+    #[test]
+    fn date_and_negation() {
+        assert_eq!(matching("date:2024-02 not:assets"), vec!["expenses:rent"]);
+        assert_eq!(matching("payee:^shop$ not:real:"), vec!["budget:food"]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!("acct:(".parse::<Query>().is_err());
+        assert!("status:x".parse::<Query>().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+pub mod balance;
+pub mod budget;
+pub mod forecast;
+pub mod register;
+pub mod statement;
+
+use std::borrow::Cow;
+
+use crate::component::period::interval::Interval;
+use crate::conversion::balancing_cost;
+use crate::directive::transaction::{posting::Posting, Periodic, Simple as Transaction};
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+
+// posting together with its transaction, and its amount inferred if it was omitted
+#[derive(Clone, Debug)]
+pub(crate) struct Entry<'a> {
+    pub transaction: &'a Transaction,
+    // owned for the posting to the default account, which is not written in the journal
+    pub posting: Cow<'a, Posting>,
+    pub amount: MixedAmount,
+}
+
+// postings of all transactions, in journal order. an amountless posting gets the amount that
+// balances the other real postings, at cost. a transaction with a single posting is balanced by
+// a posting to the default account, if one is set.
+pub(crate) fn entries(directives: &[Directive]) -> Result<Vec<Entry<'_>>, Overflow> {
+    let mut entries = Vec::new();
+    let mut default_account = None;
+    for directive in directives {
+        let transaction = match directive {
+            Directive::DefaultAccount(directive) => {
+                default_account = Some(&directive.account_name);
+                continue;
+            }
+            Directive::Transaction(transaction) => transaction,
+            _ => continue,
+        };
+        let mut balance = MixedAmount::default();
+        for posting in &transaction.postings {
+            if let (Some(amount), false) = (&posting.amount, posting.is_virtual) {
+                balance.add(&balancing_cost(
+                    amount,
+                    posting.lot.as_ref(),
+                    posting.price.as_ref(),
+                )?)?;
+            }
+        }
+        let default_posting = match (default_account, transaction.postings.as_slice()) {
+            (Some(account_name), [_]) => Some(Posting {
+                status: None,
+                account_name: account_name.clone(),
+                is_virtual: false,
+                amount: None,
+                lot: None,
+                price: None,
+                assertion: None,
+                comment: None,
+            }),
+            _ => None,
+        };
+        let postings = transaction
+            .postings
+            .iter()
+            .map(Cow::Borrowed)
+            .chain(default_posting.map(Cow::Owned));
+        let mut balance = Some(balance.negated());
+        for posting in postings {
+            let amount = match &posting.amount {
+                Some(amount) => MixedAmount::from_amount(amount),
+                None if posting.is_virtual => MixedAmount::default(),
+                None => balance.take().unwrap_or_default(),
+            };
+            entries.push(Entry {
+                transaction,
+                posting,
+                amount,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+// occurrences of a periodic transaction between the dates. they start on the periodic
+// transaction's begin date, otherwise at the start of the interval containing the begin date.
+pub(crate) fn generate(
+    periodic: &Periodic,
+    begin: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> Vec<Transaction> {
+    let period = &periodic.period;
+    let end = period.end.map_or(end, |period_end| period_end.min(end));
+    let mut dates = Vec::new();
+    match &period.interval {
+        Some(interval) => {
+            let mut date = period.begin.unwrap_or_else(|| interval.start(begin));
+            while date < end {
+                if begin <= date {
+                    dates.push(date);
+                }
+                date = interval.next(date);
+            }
+        }
+        None => dates.extend(period.begin.filter(|date| begin <= *date && *date < end)),
+    }
+    dates
+        .into_iter()
+        .map(|date| Transaction {
+            date,
+            status: periodic.status.clone(),
+            code: periodic.code.clone(),
+            payee: periodic.payee.clone(),
+            description: periodic.description.clone(),
+            postings: periodic.postings.clone(),
+            comment: None,
+            is_generated: true,
+        })
+        .collect()
+}
+
+// begin (inclusive) and end (exclusive) dates of report columns. without interval there is a
+// single column for the whole report period.
+pub(crate) fn columns(
+    interval: Option<&Interval>,
+    begin: chrono::NaiveDate,
+    end: chrono::NaiveDate,
+) -> Vec<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let Some(interval) = interval else {
+        return if begin < end {
+            vec![(begin, end)]
+        } else {
+            Vec::new()
+        };
+    };
+    let mut columns = Vec::new();
+    let mut start = interval.start(begin);
+    while start < end {
+        let next = interval.next(start);
+        columns.push((start, next));
+        start = next;
+    }
+    columns
+}
+
+// report period from the given dates, falling back to the dates of the first and last entry
+pub(crate) fn span(
+    entries: &[Entry],
+    begin: Option<chrono::NaiveDate>,
+    end: Option<chrono::NaiveDate>,
+) -> Option<(chrono::NaiveDate, chrono::NaiveDate)> {
+    let dates = entries.iter().map(|entry| entry.transaction.date);
+    let begin = begin.or_else(|| dates.clone().min())?;
+    let end = end.or_else(|| dates.max().and_then(|date| date.succ_opt()))?;
+    Some((begin, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inferred_amount() {
+        let directives = crate::parse(
+            "2024-01-01
+    assets:gold  10 gold @@ $500
+    assets:euro  10 EUR @ $1.1
+    (budget)
+    assets:cash
+",
+        )
+        .unwrap();
+        let amounts = entries(&directives)
+            .unwrap()
+            .iter()
+            .map(|entry| entry.amount.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(amounts, vec!["10 gold", "10 EUR", "0", "$-511.0"]);
+    }
+
+    #[test]
+    fn default_account() {
+        let directives = crate::parse(
+            "2024-01-01 before
+    income:salary  $-1
+bucket assets:checking
+2024-01-02 salary
+    income:salary  $-10
+2024-01-03 two postings
+    expenses:food  $5
+    assets:cash
+",
+        )
+        .unwrap();
+        let postings = entries(&directives)
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {}",
+                    entry.posting.account_name.0.join(":"),
+                    entry.amount
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            postings,
+            vec![
+                "income:salary $-1",
+                "income:salary $-10",
+                "assets:checking $10",
+                "expenses:food $5",
+                "assets:cash $-5",
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_dates() {
+        let directives = crate::parse(
+            "~ every 2 weeks from 2024-01-03 to 2024-02-14
+    (expenses:rent)  $1
+~ monthly
+    (expenses:food)  $1
+",
+        )
+        .unwrap();
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let dates = directives
+            .iter()
+            .map(|directive| {
+                let Directive::PeriodicTransaction(periodic) = directive else {
+                    panic!("expected periodic transaction");
+                };
+                generate(periodic, date(1, 10), date(3, 1))
+                    .iter()
+                    .map(|transaction| transaction.date)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            dates,
+            vec![vec![date(1, 17), date(1, 31)], vec![date(2, 1)],]
+        );
+    }
+
+    #[test]
+    fn monthly_columns() {
+        let date = |month, day| chrono::NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        assert_eq!(
+            columns(Some(&Interval::NthMonth(1)), date(1, 15), date(3, 1)),
+            vec![(date(1, 1), date(2, 1)), (date(2, 1), date(3, 1))]
+        );
+        assert_eq!(
+            columns(None, date(1, 15), date(3, 1)),
+            vec![(date(1, 15), date(3, 1))]
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::component::account_name::AccountName;
+use crate::component::period::interval::Interval;
+use crate::conversion::{cost, Conversion};
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::price_db::PriceDb;
+use crate::report::{columns, entries, span, Entry};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    // accounts with postings, each with its own balance
+    #[default]
+    Flat,
+    // accounts with their parents, each balance including subaccounts
+    Tree,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    pub begin: Option<chrono::NaiveDate>,
+    // exclusive
+    pub end: Option<chrono::NaiveDate>,
+    // one column per period, instead of a single column for the whole report
+    pub interval: Option<Interval>,
+    // deeper accounts are aggregated into their parent at this depth
+    pub depth: Option<usize>,
+    pub mode: Mode,
+    // include accounts whose balance is zero
+    pub empty: bool,
+    // balances at the end of each column, including postings before the report period, instead
+    // of balance changes during each column
+    pub historical: bool,
+    // amounts at cost, or at market value at the end of each column unless a date is given
+    pub conversion: Option<Conversion>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub account_name: AccountName,
+    // number of parent accounts shown above the row, always zero in flat mode
+    pub indent: usize,
+    // balance change in each column, or balance at its end
+    pub amounts: Vec<MixedAmount>,
+    // sum of the balance changes, or the last balance
+    pub total: MixedAmount,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    // begin (inclusive) and end (exclusive) date of each column
+    pub columns: Vec<(chrono::NaiveDate, chrono::NaiveDate)>,
+    pub rows: Vec<Row>,
+    pub totals: Vec<MixedAmount>,
+    pub total: MixedAmount,
+}
+
+#[allow(clippy::missing_errors_doc)]
+pub fn balance(directives: &[Directive], options: &Options) -> Result<Report, Overflow> {
+    let entries = entries(directives)?;
+    let columns = columns_of(&entries, options);
+    from_entries(
+        &entries,
+        columns,
+        options,
+        &PriceDb::from_directives(directives),
+    )
+}
+
+pub(crate) fn columns_of(
+    entries: &[Entry],
+    options: &Options,
+) -> Vec<(chrono::NaiveDate, chrono::NaiveDate)> {
+    span(entries, options.begin, options.end)
+        .map(|(begin, end)| columns(options.interval.as_ref(), begin, end))
+        .unwrap_or_default()
+}
+
+pub(crate) fn from_entries(
+    entries: &[Entry],
+    columns: Vec<(chrono::NaiveDate, chrono::NaiveDate)>,
+    options: &Options,
+    prices: &PriceDb,
+) -> Result<Report, Overflow> {
+    let mut balances = BTreeMap::<AccountName, Vec<MixedAmount>>::new();
+    for entry in entries {
+        let date = entry.transaction.date;
+        let column = columns.partition_point(|(_, end)| *end <= date);
+        let is_before = columns.first().is_some_and(|(begin, _)| date < *begin);
+        let column = match columns.get(column) {
+            // historical balances start with everything before the first column
+            _ if is_before && options.historical => 0,
+            Some((begin, _)) if *begin <= date => column,
+            _ => continue,
+        };
+        let account_name = match options.depth {
+            Some(depth) => AccountName(
+                entry
+                    .posting
+                    .account_name
+                    .0
+                    .iter()
+                    .take(depth)
+                    .cloned()
+                    .collect(),
+            ),
+            None => entry.posting.account_name.clone(),
+        };
+        if account_name.0.is_empty() {
+            continue;
+        }
+        let amount = match (&options.conversion, &entry.posting.amount) {
+            (Some(Conversion::Cost), Some(amount)) => MixedAmount::from_amount(&cost(
+                amount,
+                entry.posting.lot.as_ref(),
+                entry.posting.price.as_ref(),
+            )?),
+            _ => entry.amount.clone(),
+        };
+        balances
+            .entry(account_name)
+            .or_insert_with(|| vec![MixedAmount::default(); columns.len()])[column]
+            .add_mixed(&amount)?;
+    }
+
+    if options.mode == Mode::Tree {
+        balances = roll_up(&balances, columns.len())?;
+    }
+    if options.historical {
+        for amounts in balances.values_mut() {
+            accumulate(amounts)?;
+        }
+    }
+    if let Some(conversion) = &options.conversion {
+        for amounts in balances.values_mut() {
+            for (amount, (_, end)) in amounts.iter_mut().zip(&columns) {
+                let date = end.pred_opt().unwrap_or(*end);
+                *amount = conversion.value(amount, prices, date)?;
+            }
+        }
+    }
+    let visible = visible(&balances, options);
+    let mut totals = vec![MixedAmount::default(); columns.len()];
+    let mut rows = Vec::new();
+    for (account_name, amounts) in balances {
+        if !visible.contains(&account_name) {
+            continue;
+        }
+        // parents already include their subaccounts
+        if options.mode == Mode::Flat || account_name.0.len() == 1 {
+            for (total, amount) in totals.iter_mut().zip(&amounts) {
+                total.add_mixed(amount)?;
+            }
+        }
+        rows.push(Row {
+            indent: match options.mode {
+                Mode::Flat => 0,
+                Mode::Tree => account_name.0.len() - 1,
+            },
+            account_name,
+            total: total(&amounts, options)?,
+            amounts,
+        });
+    }
+
+    Ok(Report {
+        total: total(&totals, options)?,
+        columns,
+        rows,
+        totals,
+    })
+}
+
+// adds every account's balance to all of its parents
+fn roll_up(
+    balances: &BTreeMap<AccountName, Vec<MixedAmount>>,
+    columns: usize,
+) -> Result<BTreeMap<AccountName, Vec<MixedAmount>>, Overflow> {
+    let mut inclusive = BTreeMap::<AccountName, Vec<MixedAmount>>::new();
+    for (account_name, amounts) in balances {
+        for depth in 1..=account_name.0.len() {
+            let parent = AccountName(account_name.0[..depth].to_vec());
+            let parent_amounts = inclusive
+                .entry(parent)
+                .or_insert_with(|| vec![MixedAmount::default(); columns]);
+            for (parent_amount, amount) in parent_amounts.iter_mut().zip(amounts) {
+                parent_amount.add_mixed(amount)?;
+            }
+        }
+    }
+    Ok(inclusive)
+}
+
+// accounts with a non-zero balance, and in tree mode their parents
+fn visible(
+    balances: &BTreeMap<AccountName, Vec<MixedAmount>>,
+    options: &Options,
+) -> BTreeSet<AccountName> {
+    let mut visible = BTreeSet::new();
+    for (account_name, amounts) in balances {
+        if !options.empty && amounts.iter().all(MixedAmount::is_zero) {
+            continue;
+        }
+        visible.insert(account_name.clone());
+        if options.mode == Mode::Tree {
+            for depth in 1..account_name.0.len() {
+                visible.insert(AccountName(account_name.0[..depth].to_vec()));
+            }
+        }
+    }
+    visible
+}
+
+// running sum of balance changes
+fn accumulate(amounts: &mut [MixedAmount]) -> Result<(), Overflow> {
+    let mut balance = MixedAmount::default();
+    for amount in amounts {
+        balance.add_mixed(amount)?;
+        amount.clone_from(&balance);
+    }
+    Ok(())
+}
+
+fn total(amounts: &[MixedAmount], options: &Options) -> Result<MixedAmount, Overflow> {
+    if options.historical {
+        return Ok(amounts.last().cloned().unwrap_or_default());
+    }
+    let mut total = MixedAmount::default();
+    for amount in amounts {
+        total.add_mixed(amount)?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::commodity::Commodity;
+
+    use super::*;
+
+    fn directives() -> Vec<Directive> {
+        crate::parse(
+            "2024-01-05
+    expenses:food:groceries  $10
+    expenses:food:dining  $20
+    assets:cash
+2024-02-10
+    expenses:rent  $500
+    expenses:food:groceries  $5
+    assets:bank
+2024-02-11
+    assets:bank  $100
+    assets:cash
+",
+        )
+        .unwrap()
+    }
+
+    fn rows(report: &Report) -> Vec<(String, usize, Vec<String>)> {
+        report
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.account_name.0.join(":"),
+                    row.indent,
+                    row.amounts.iter().map(ToString::to_string).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn flat() {
+        let report = balance(&directives(), &Options::default()).unwrap();
+        assert_eq!(
+            rows(&report),
+            vec![
+                (String::from("assets:bank"), 0, vec![String::from("$-405")]),
+                (String::from("assets:cash"), 0, vec![String::from("$-130")]),
+                (
+                    String::from("expenses:food:dining"),
+                    0,
+                    vec![String::from("$20")]
+                ),
+                (
+                    String::from("expenses:food:groceries"),
+                    0,
+                    vec![String::from("$15")]
+                ),
+                (String::from("expenses:rent"), 0, vec![String::from("$500")]),
+            ]
+        );
+        assert!(report.total.is_zero());
+    }
+
+    #[test]
+    fn tree_with_depth() {
+        let report = balance(
+            &directives(),
+            &Options {
+                depth: Some(2),
+                mode: Mode::Tree,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            rows(&report),
+            vec![
+                (String::from("assets"), 0, vec![String::from("$-535")]),
+                (String::from("assets:bank"), 1, vec![String::from("$-405")]),
+                (String::from("assets:cash"), 1, vec![String::from("$-130")]),
+                (String::from("expenses"), 0, vec![String::from("$535")]),
+                (String::from("expenses:food"), 1, vec![String::from("$35")]),
+                (String::from("expenses:rent"), 1, vec![String::from("$500")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_columns() {
+        let options = Options {
+            interval: Some(Interval::NthMonth(1)),
+            depth: Some(1),
+            ..Options::default()
+        };
+        let report = balance(&directives(), &options).unwrap();
+        assert_eq!(
+            rows(&report),
+            vec![
+                (
+                    String::from("assets"),
+                    0,
+                    vec![String::from("$-30"), String::from("$-505")]
+                ),
+                (
+                    String::from("expenses"),
+                    0,
+                    vec![String::from("$30"), String::from("$505")]
+                ),
+            ]
+        );
+
+        // begin date is adjusted to the start of its period
+        let report = balance(
+            &directives(),
+            &Options {
+                begin: chrono::NaiveDate::from_ymd_opt(2024, 2, 5),
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            report.columns,
+            vec![(
+                chrono::NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+            )]
+        );
+        assert_eq!(
+            rows(&report),
+            vec![
+                (String::from("assets"), 0, vec![String::from("$-505")]),
+                (String::from("expenses"), 0, vec![String::from("$505")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn historical() {
+        let report = balance(
+            &directives(),
+            &Options {
+                begin: chrono::NaiveDate::from_ymd_opt(2024, 2, 11),
+                interval: Some(Interval::NthDay(1)),
+                depth: Some(1),
+                historical: true,
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            rows(&report),
+            vec![
+                (String::from("assets"), 0, vec![String::from("$-535")]),
+                (String::from("expenses"), 0, vec![String::from("$535")]),
+            ]
+        );
+        assert!(report.total.is_zero());
+    }
+
+    #[test]
+    fn cost_and_value() {
+        let directives = crate::parse(
+            "2024-01-05
+    assets:stock  10 AAPL {$50}
+    assets:bank
+2024-02-10
+    assets:stock  -4 AAPL {$50} @ $60
+    assets:bank  $240
+    income:gains  $-40
+P 2024-01-31 AAPL $55
+P 2024-02-29 AAPL $70
+",
+        )
+        .unwrap();
+        let options = Options {
+            interval: Some(Interval::NthMonth(1)),
+            depth: Some(1),
+            historical: true,
+            ..Options::default()
+        };
+        let report = |conversion| {
+            rows(
+                &balance(
+                    &directives,
+                    &Options {
+                        conversion: Some(conversion),
+                        ..options.clone()
+                    },
+                )
+                .unwrap(),
+            )
+        };
+        assert_eq!(
+            rows(&balance(&directives, &options).unwrap()),
+            vec![
+                (
+                    String::from("assets"),
+                    0,
+                    vec![
+                        String::from("$-500, 10 AAPL"),
+                        String::from("$-260, 6 AAPL")
+                    ]
+                ),
+                (
+                    String::from("income"),
+                    0,
+                    vec![String::from("0"), String::from("$-40")]
+                ),
+            ]
+        );
+        assert_eq!(
+            report(Conversion::Cost)[0],
+            (
+                String::from("assets"),
+                0,
+                vec![String::from("0"), String::from("$40")]
+            )
+        );
+        assert_eq!(
+            report(Conversion::Value(None))[0],
+            (
+                String::from("assets"),
+                0,
+                vec![String::from("$50"), String::from("$160")]
+            )
+        );
+        assert_eq!(
+            report(Conversion::Exchange(
+                Commodity::from_str("$"),
+                chrono::NaiveDate::from_ymd_opt(2024, 1, 31)
+            ))[0],
+            (
+                String::from("assets"),
+                0,
+                vec![String::from("$50"), String::from("$70")]
+            )
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let directives = crate::parse(
+            "2024-01-01
+    assets:cash  18446744073709551615 EUR
+    equity
+2024-01-02
+    assets:cash  1 EUR
+    equity
+",
+        )
+        .unwrap();
+        assert_eq!(balance(&directives, &Options::default()), Err(Overflow));
+    }
+
+    #[test]
+    fn price_overflow() {
+        let directives = crate::parse(
+            "2024-01-01 x
+    a  10000000000 X @ 10000000000000000000 Y
+    b
+",
+        )
+        .unwrap();
+        assert_eq!(balance(&directives, &Options::default()), Err(Overflow));
+    }
+}
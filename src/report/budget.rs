@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::component::account_name::AccountName;
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::price_db::PriceDb;
+use crate::report::balance::{self, Mode, Options};
+use crate::report::{entries, generate};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cell {
+    pub actual: MixedAmount,
+    pub budget: MixedAmount,
+}
+
+impl Cell {
+    // actual amount as a percentage of the budget, when both are in the same single commodity
+    #[must_use]
+    pub fn percentage(&self) -> Option<Decimal> {
+        let mut budget = self.budget.amounts();
+        let (Some(budget), None) = (budget.next(), budget.next()) else {
+            return None;
+        };
+        if self
+            .actual
+            .amounts()
+            .any(|actual| actual.commodity != budget.commodity)
+        {
+            return None;
+        }
+        let actual = self.actual.get(&budget.commodity);
+        actual
+            .checked_mul(Decimal::ONE_HUNDRED)?
+            .checked_div(budget.to_decimal())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub account_name: AccountName,
+    pub indent: usize,
+    // actual amounts include subaccounts
+    pub cells: Vec<Cell>,
+    pub total: Cell,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    // begin (inclusive) and end (exclusive) date of each column
+    pub columns: Vec<(chrono::NaiveDate, chrono::NaiveDate)>,
+    // accounts with budget goals from periodic transactions
+    pub rows: Vec<Row>,
+}
+
+// compares actual balance changes with goals from periodic transactions, expanded over the
+// report period
+#[allow(clippy::missing_errors_doc)]
+pub fn budget(directives: &[Directive], options: &Options) -> Result<Report, Overflow> {
+    let options = Options {
+        mode: Mode::Tree,
+        historical: false,
+        ..options.clone()
+    };
+    let actual_entries = entries(directives)?;
+    let columns = balance::columns_of(&actual_entries, &options);
+    let (Some((begin, _)), Some((_, end))) = (columns.first(), columns.last()) else {
+        return Ok(Report {
+            columns,
+            rows: Vec::new(),
+        });
+    };
+
+    let goals = directives
+        .iter()
+        .filter_map(|directive| match directive {
+            Directive::PeriodicTransaction(periodic) => Some(periodic),
+            _ => None,
+        })
+        .flat_map(|periodic| generate(periodic, *begin, *end))
+        .map(Directive::Transaction)
+        .collect::<Vec<_>>();
+    let prices = PriceDb::from_directives(directives);
+    let budget = balance::from_entries(&entries(&goals)?, columns.clone(), &options, &prices)?;
+    let actual = balance::from_entries(&actual_entries, columns.clone(), &options, &prices)?
+        .rows
+        .into_iter()
+        .map(|row| (row.account_name, (row.amounts, row.total)))
+        .collect::<HashMap<_, _>>();
+
+    let rows = budget
+        .rows
+        .into_iter()
+        .map(|row| {
+            let (actual_amounts, actual_total) =
+                actual.get(&row.account_name).cloned().unwrap_or_else(|| {
+                    (
+                        vec![MixedAmount::default(); columns.len()],
+                        MixedAmount::default(),
+                    )
+                });
+            Row {
+                indent: row.indent,
+                cells: row
+                    .amounts
+                    .into_iter()
+                    .zip(actual_amounts)
+                    .map(|(budget, actual)| Cell { actual, budget })
+                    .collect(),
+                total: Cell {
+                    actual: actual_total,
+                    budget: row.total,
+                },
+                account_name: row.account_name,
+            }
+        })
+        .collect();
+
+    Ok(Report { columns, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::period::interval::Interval;
+
+    use super::*;
+
+    #[test]
+    fn budget_vs_actual() {
+        let directives = crate::parse(
+            "~ monthly
+    (expenses:food)  $400
+    (expenses:rent)  $1000
+
+2024-01-05
+    expenses:food:groceries  $100
+    expenses:food:dining  $200
+    expenses:fun  $50
+    assets:bank
+2024-02-01
+    expenses:rent  $1000
+    assets:bank
+",
+        )
+        .unwrap();
+        let report = budget(
+            &directives,
+            &Options {
+                interval: Some(Interval::NthMonth(1)),
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        let rows = report
+            .rows
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .cells
+                    .iter()
+                    .map(|cell| {
+                        format!(
+                            "{} of {} ({})",
+                            cell.actual,
+                            cell.budget,
+                            cell.percentage()
+                                .map_or(String::from("-"), |p| p.round().to_string())
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                (row.account_name.0.join(":"), cells)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    String::from("expenses"),
+                    vec![
+                        String::from("$350 of $1400 (25)"),
+                        String::from("$1000 of $1400 (71)"),
+                    ]
+                ),
+                (
+                    String::from("expenses:food"),
+                    vec![
+                        String::from("$300 of $400 (75)"),
+                        String::from("0 of $400 (0)"),
+                    ]
+                ),
+                (
+                    String::from("expenses:rent"),
+                    vec![
+                        String::from("0 of $1000 (0)"),
+                        String::from("$1000 of $1000 (100)"),
+                    ]
+                ),
+            ]
+        );
+        assert_eq!(
+            report.rows[1].total.percentage(),
+            Some(Decimal::new(375, 1))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use crate::directive::Directive;
+use crate::report::generate;
+
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    // defaults to the day after the last transaction in the journal
+    pub begin: Option<chrono::NaiveDate>,
+    // exclusive, defaults to six months after the begin date
+    pub end: Option<chrono::NaiveDate>,
+}
+
+// transactions generated from periodic transactions, ordered by date. they are marked as
+// generated, and can be appended to the journal's directives to project future balances.
+#[must_use]
+pub fn forecast(directives: &[Directive], options: &Options) -> Vec<Directive> {
+    let last_date = directives
+        .iter()
+        .filter_map(|directive| match directive {
+            Directive::Transaction(transaction) => Some(transaction.date),
+            _ => None,
+        })
+        .max();
+    let begin = options
+        .begin
+        .or_else(|| last_date.and_then(|date| date.succ_opt()))
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    let Some(end) = options
+        .end
+        .or_else(|| begin.checked_add_months(chrono::Months::new(6)))
+    else {
+        return Vec::new();
+    };
+
+    let mut transactions = directives
+        .iter()
+        .filter_map(|directive| match directive {
+            Directive::PeriodicTransaction(periodic) => Some(generate(periodic, begin, end)),
+            _ => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    transactions.sort_by_key(|transaction| transaction.date);
+    transactions
+        .into_iter()
+        .map(Directive::Transaction)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::balance::{balance, Options as BalanceOptions};
+
+    use super::*;
+
+    fn directives() -> Vec<Directive> {
+        crate::parse(
+            "~ monthly  rent
+    expenses:rent  $1000
+    assets:bank
+~ every 2 months from 2024-01-15  insurance
+    expenses:insurance  $200
+    assets:bank
+2024-03-10 salary
+    assets:bank  $5000
+    income:salary
+",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn after_last_transaction() {
+        let forecast = forecast(&directives(), &Options::default());
+        let transactions = forecast
+            .iter()
+            .map(|directive| match directive {
+                Directive::Transaction(transaction) => {
+                    assert!(transaction.is_generated);
+                    (transaction.date.to_string(), transaction.payee.clone())
+                }
+                _ => panic!("expected transaction"),
+            })
+            .collect::<Vec<_>>();
+        let expected = [
+            ("2024-03-15", "insurance"),
+            ("2024-04-01", "rent"),
+            ("2024-05-01", "rent"),
+            ("2024-05-15", "insurance"),
+            ("2024-06-01", "rent"),
+            ("2024-07-01", "rent"),
+            ("2024-07-15", "insurance"),
+            ("2024-08-01", "rent"),
+            ("2024-09-01", "rent"),
+        ]
+        .map(|(date, payee)| (String::from(date), String::from(payee)));
+        assert_eq!(transactions, expected);
+    }
+
+    #[test]
+    fn projected_balance() {
+        let mut directives = directives();
+        directives.extend(forecast(
+            &directives,
+            &Options {
+                end: chrono::NaiveDate::from_ymd_opt(2024, 5, 1),
+                ..Options::default()
+            },
+        ));
+        let report = balance(
+            &directives,
+            &BalanceOptions {
+                depth: Some(1),
+                ..BalanceOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(report.rows[0].account_name.0, vec![String::from("assets")]);
+        assert_eq!(report.rows[0].total.to_string(), "$3800");
+    }
+}
@@ -0,0 +1,124 @@
+use crate::component::account_name::AccountName;
+use crate::directive::transaction::status::Status;
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::query::Query;
+use crate::report::entries;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    pub date: chrono::NaiveDate,
+    // posting's own status, otherwise its transaction's
+    pub status: Option<Status>,
+    pub payee: String,
+    pub description: Option<String>,
+    pub account_name: AccountName,
+    pub is_virtual: bool,
+    pub amount: MixedAmount,
+    // sum of the amounts of this and all previous rows
+    pub total: MixedAmount,
+}
+
+// postings matching the query, ordered by date, with a running total
+#[allow(clippy::missing_errors_doc)]
+pub fn register(directives: &[Directive], query: &Query) -> Result<Vec<Row>, Overflow> {
+    let mut entries = entries(directives)?;
+    entries.retain(|entry| query.matches(entry));
+    entries.sort_by_key(|entry| entry.transaction.date);
+
+    let mut total = MixedAmount::default();
+    let mut rows = Vec::with_capacity(entries.len());
+    for entry in entries {
+        total.add_mixed(&entry.amount)?;
+        rows.push(Row {
+            date: entry.transaction.date,
+            status: entry
+                .posting
+                .status
+                .clone()
+                .or_else(|| entry.transaction.status.clone()),
+            payee: entry.transaction.payee.clone(),
+            description: entry.transaction.description.clone(),
+            account_name: entry.posting.account_name.clone(),
+            is_virtual: entry.posting.is_virtual,
+            amount: entry.amount,
+            total: total.clone(),
+        });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(query: &str) -> Vec<(String, String, String, String)> {
+        let directives = crate::parse(
+            "2024-02-01 * salary
+    assets:bank  $1000
+    income:salary
+2024-01-15 ! shop | groceries
+    expenses:food  $30
+    expenses:food  5 EUR
+    (budget:food)  $-30
+    assets:bank  $-30
+    assets:cash  -5 EUR
+",
+        )
+        .unwrap();
+        register(&directives, &query.parse().unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|row| {
+                (
+                    row.date.to_string(),
+                    row.account_name.0.join(":"),
+                    row.amount.to_string(),
+                    row.total.to_string(),
+                )
+            })
+            .collect()
+    }
+
+    fn row(
+        date: &str,
+        account: &str,
+        amount: &str,
+        total: &str,
+    ) -> (String, String, String, String) {
+        (
+            String::from(date),
+            String::from(account),
+            String::from(amount),
+            String::from(total),
+        )
+    }
+
+    #[test]
+    fn running_total() {
+        assert_eq!(
+            rows("expenses assets:bank"),
+            vec![
+                row("2024-01-15", "expenses:food", "$30", "$30"),
+                row("2024-01-15", "expenses:food", "5 EUR", "$30, 5 EUR"),
+                row("2024-01-15", "assets:bank", "$-30", "5 EUR"),
+                row("2024-02-01", "assets:bank", "$1000", "$1000, 5 EUR"),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_and_virtual() {
+        assert_eq!(
+            rows("status:! not:real:"),
+            vec![row("2024-01-15", "budget:food", "$-30", "$-30")]
+        );
+        assert_eq!(
+            rows("status:*"),
+            vec![
+                row("2024-02-01", "assets:bank", "$1000", "$1000"),
+                row("2024-02-01", "income:salary", "$-1000", "0"),
+            ]
+        );
+    }
+}
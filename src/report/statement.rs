@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use crate::component::account_name::AccountName;
+use crate::directive::account::AccountType;
+use crate::directive::Directive;
+use crate::mixed_amount::{MixedAmount, Overflow};
+use crate::price_db::PriceDb;
+use crate::report::balance::{self, Options};
+use crate::report::{entries, Entry};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Section {
+    pub title: String,
+    // amounts of normally negative sections, like liabilities and revenues, are negated
+    pub report: balance::Report,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statement {
+    pub title: String,
+    // begin (inclusive) and end (exclusive) date of each column
+    pub columns: Vec<(chrono::NaiveDate, chrono::NaiveDate)>,
+    pub sections: Vec<Section>,
+    // first section's totals minus the others'
+    pub net: Vec<MixedAmount>,
+    pub net_total: MixedAmount,
+}
+
+// assets and liabilities at the end of each column, including postings before the report period
+#[allow(clippy::missing_errors_doc)]
+pub fn balance_sheet(directives: &[Directive], options: &Options) -> Result<Statement, Overflow> {
+    statement(
+        directives,
+        &Options {
+            historical: true,
+            ..options.clone()
+        },
+        "Balance Sheet",
+        &[
+            ("Assets", &[AccountType::Asset, AccountType::Cash], false),
+            ("Liabilities", &[AccountType::Liability], true),
+        ],
+    )
+}
+
+// revenues and expenses during each column
+#[allow(clippy::missing_errors_doc)]
+pub fn income_statement(
+    directives: &[Directive],
+    options: &Options,
+) -> Result<Statement, Overflow> {
+    statement(
+        directives,
+        options,
+        "Income Statement",
+        &[
+            ("Revenues", &[AccountType::Revenue], true),
+            ("Expenses", &[AccountType::Expense], false),
+        ],
+    )
+}
+
+fn statement(
+    directives: &[Directive],
+    options: &Options,
+    title: &str,
+    sections: &[(&str, &[AccountType], bool)],
+) -> Result<Statement, Overflow> {
+    let declared = declared_types(directives);
+    let entries = entries(directives)?;
+    let columns = balance::columns_of(&entries, options);
+    let prices = PriceDb::from_directives(directives);
+
+    let mut statement_sections = Vec::new();
+    for (title, types, is_negated) in sections {
+        let entries = entries
+            .iter()
+            .filter(|entry| {
+                account_type(&entry.posting.account_name, &declared)
+                    .is_some_and(|account_type| types.contains(&account_type))
+            })
+            .cloned()
+            .collect::<Vec<Entry>>();
+        let mut report = balance::from_entries(&entries, columns.clone(), options, &prices)?;
+        if *is_negated {
+            negate(&mut report);
+        }
+        statement_sections.push(Section {
+            title: (*title).to_string(),
+            report,
+        });
+    }
+
+    let mut net = vec![MixedAmount::default(); columns.len()];
+    for (index, section) in statement_sections.iter().enumerate() {
+        for (net, total) in net.iter_mut().zip(&section.report.totals) {
+            if index == 0 {
+                net.add_mixed(total)?;
+            } else {
+                net.add_mixed(&total.negated())?;
+            }
+        }
+    }
+    let mut net_total = MixedAmount::default();
+    if options.historical {
+        net_total = net.last().cloned().unwrap_or_default();
+    } else {
+        for amount in &net {
+            net_total.add_mixed(amount)?;
+        }
+    }
+
+    Ok(Statement {
+        title: title.to_string(),
+        columns,
+        sections: statement_sections,
+        net,
+        net_total,
+    })
+}
+
+fn negate(report: &mut balance::Report) {
+    for row in &mut report.rows {
+        for amount in &mut row.amounts {
+            *amount = amount.negated();
+        }
+        row.total = row.total.negated();
+    }
+    for amount in &mut report.totals {
+        *amount = amount.negated();
+    }
+    report.total = report.total.negated();
+}
+
+fn declared_types(directives: &[Directive]) -> HashMap<AccountName, AccountType> {
+    directives
+        .iter()
+        .filter_map(|directive| match directive {
+            Directive::Account(account) => account
+                .account_type
+                .map(|account_type| (account.account_name.clone(), account_type)),
+            _ => None,
+        })
+        .collect()
+}
+
+// type declared for the account or its nearest parent, otherwise guessed from the top-level name
+pub(crate) fn account_type(
+    account_name: &AccountName,
+    declared: &HashMap<AccountName, AccountType>,
+) -> Option<AccountType> {
+    let declared = (1..=account_name.0.len())
+        .rev()
+        .find_map(|depth| declared.get(&AccountName(account_name.0[..depth].to_vec())));
+    if let Some(account_type) = declared {
+        return Some(*account_type);
+    }
+    match account_name.0.first()?.to_lowercase().as_str() {
+        "asset" | "assets" => Some(AccountType::Asset),
+        "debt" | "debts" | "liability" | "liabilities" => Some(AccountType::Liability),
+        "equity" => Some(AccountType::Equity),
+        "income" | "incomes" | "revenue" | "revenues" => Some(AccountType::Revenue),
+        "expense" | "expenses" => Some(AccountType::Expense),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::period::interval::Interval;
+
+    use super::*;
+
+    fn directives() -> Vec<Directive> {
+        crate::parse(
+            "account savings  ; type: A
+account cc  ; type:L
+2024-01-01 opening
+    savings  $1000
+    equity:opening
+2024-02-01 salary
+    income:salary  $-3000
+    savings
+2024-02-05 rent
+    expenses:rent  $1200
+    cc
+2024-03-01 bonus
+    revenues  $-500
+    savings
+",
+        )
+        .unwrap()
+    }
+
+    fn section_totals(statement: &Statement) -> Vec<(String, Vec<String>)> {
+        statement
+            .sections
+            .iter()
+            .map(|section| {
+                (
+                    section.title.clone(),
+                    section
+                        .report
+                        .totals
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn balance_sheet_is_historical() {
+        let statement = balance_sheet(
+            &directives(),
+            &Options {
+                begin: chrono::NaiveDate::from_ymd_opt(2024, 2, 1),
+                interval: Some(Interval::NthMonth(1)),
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            section_totals(&statement),
+            vec![
+                (
+                    String::from("Assets"),
+                    vec![String::from("$4000"), String::from("$4500")]
+                ),
+                (
+                    String::from("Liabilities"),
+                    vec![String::from("$1200"), String::from("$1200")]
+                ),
+            ]
+        );
+        assert_eq!(statement.net_total.to_string(), "$3300");
+    }
+
+    #[test]
+    fn income_statement_by_period() {
+        let statement = income_statement(
+            &directives(),
+            &Options {
+                interval: Some(Interval::NthMonth(1)),
+                ..Options::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            section_totals(&statement),
+            vec![
+                (
+                    String::from("Revenues"),
+                    vec![
+                        String::from("0"),
+                        String::from("$3000"),
+                        String::from("$500")
+                    ]
+                ),
+                (
+                    String::from("Expenses"),
+                    vec![String::from("0"), String::from("$1200"), String::from("0")]
+                ),
+            ]
+        );
+        let net = statement
+            .net
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(net, vec!["0", "$1800", "$500"]);
+        assert_eq!(statement.net_total.to_string(), "$2300");
+    }
+
+    #[test]
+    fn declared_type_is_inherited() {
+        let mut declared = HashMap::new();
+        declared.insert(
+            AccountName::from_strs(&[String::from("wallet")]),
+            AccountType::Cash,
+        );
+        let account_type = |name: &str| {
+            account_type(
+                &AccountName::from_strs(&name.split(':').map(String::from).collect::<Vec<_>>()),
+                &declared,
+            )
+        };
+        assert_eq!(account_type("wallet:btc"), Some(AccountType::Cash));
+        assert_eq!(account_type("Assets:bank"), Some(AccountType::Asset));
+        assert_eq!(account_type("other"), None);
+    }
+}
@@ -2,8 +2,24 @@ use std::time::SystemTime;
 
 use chrono::Datelike;
 
+use crate::component::account_name::AccountName;
+use crate::component::amount::style::CommodityStyle;
+use crate::component::commodity::Commodity;
+use crate::directive::alias::Alias;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct State {
     pub year: i32,
+    // defined by alias directives, in order of appearance
+    pub aliases: Vec<Alias>,
+    // given as options, applied after directive aliases and kept after "end aliases"
+    pub option_aliases: Vec<Alias>,
+    // stack of apply account directives, outermost first
+    pub parent_accounts: Vec<AccountName>,
+    // set by D directive
+    pub default_commodity: Option<(Commodity, CommodityStyle)>,
+    // set by decimal-mark directive, the other mark separates digit groups
+    pub decimal_mark: Option<char>,
 }
 
 impl Default for State {
@@ -12,6 +28,11 @@ impl Default for State {
         let datetime: chrono::DateTime<chrono::Local> = current_time.into();
         Self {
             year: datetime.year(),
+            aliases: Vec::new(),
+            option_aliases: Vec::new(),
+            parent_accounts: Vec::new(),
+            default_commodity: None,
+            decimal_mark: None,
         }
     }
 }
@@ -0,0 +1,153 @@
+use std::io::BufRead;
+
+use crate::chunk::{parse_chunk, starts_chunk, ParseError};
+use crate::directive::Directive;
+use crate::state::State;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    // errors of one chunk, with spans in the whole input
+    Parse(Vec<ParseError>),
+}
+
+// directives read from the input chunk by chunk, so that only one top-level directive is kept
+// in memory at a time. a chunk with errors is skipped after yielding them.
+pub struct Directives<R> {
+    reader: R,
+    state: State,
+    // first line of the next chunk, read ahead
+    next_line: String,
+    // offset of the next chunk in the input
+    offset: usize,
+    // directives of the current chunk, last first
+    pending: Vec<Directive>,
+    is_done: bool,
+}
+
+#[must_use]
+pub fn parse_reader<R: BufRead>(reader: R) -> Directives<R> {
+    Directives {
+        reader,
+        state: State::default(),
+        next_line: String::new(),
+        offset: 0,
+        pending: Vec::new(),
+        is_done: false,
+    }
+}
+
+impl<R: BufRead> Directives<R> {
+    // lines of the next chunk, empty at the end of the input
+    fn read_chunk(&mut self) -> std::io::Result<String> {
+        let mut chunk = std::mem::take(&mut self.next_line);
+        let mut in_block = chunk.trim_end() == "comment";
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(chunk);
+            }
+            if in_block {
+                in_block = line.trim_end() != "end comment";
+            } else if chunk.is_empty() {
+                in_block = line.trim_end() == "comment";
+            } else if starts_chunk(&line) {
+                self.next_line = line;
+                return Ok(chunk);
+            }
+            chunk.push_str(&line);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Directives<R> {
+    type Item = Result<Directive, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(directive) = self.pending.pop() {
+                return Some(Ok(directive));
+            }
+            if self.is_done {
+                return None;
+            }
+            let chunk = match self.read_chunk() {
+                Ok(chunk) if chunk.is_empty() => {
+                    self.is_done = true;
+                    return None;
+                }
+                Ok(chunk) => chunk,
+                Err(error) => {
+                    self.is_done = true;
+                    return Some(Err(Error::Io(error)));
+                }
+            };
+            let (mut directives, errors) = parse_chunk(&chunk, self.offset, &mut self.state);
+            self.offset += chunk.len();
+            if !errors.is_empty() {
+                return Some(Err(Error::Parse(errors)));
+            }
+            directives.reverse();
+            self.pending = directives;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_parse() {
+        let journal = "; journal
+Y2023
+comment
+01-01 not a transaction
+
+end comment
+
+01-05 shop
+    expenses:food  $10
+    assets:cash
+
+apply account business
+01-06 rent
+    expenses:rent  $500
+    assets:bank
+";
+        let directives = parse_reader(journal.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            format!("{directives:?}"),
+            format!("{:?}", crate::parse(journal).unwrap())
+        );
+    }
+
+    #[test]
+    fn continue_after_error() {
+        let journal = "2024-01-05 shop
+    expenses:food  $x
+2024-01-06 rent
+    expenses:rent  $500
+    assets:bank
+";
+        let mut directives = parse_reader(journal.as_bytes());
+        let Some(Err(Error::Parse(errors))) = directives.next() else {
+            panic!("expected parse error");
+        };
+        assert_eq!(errors[0].span.start, 35);
+        assert!(matches!(
+            directives.next(),
+            Some(Ok(Directive::Transaction(_)))
+        ));
+        assert!(directives.next().is_none());
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let mut directives = parse_reader(&b"2024-01-05 \xff\n"[..]);
+        assert!(matches!(directives.next(), Some(Err(Error::Io(_)))));
+        assert!(directives.next().is_none());
+    }
+}
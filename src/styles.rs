@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::component::amount::{style::CommodityStyle, Amount};
+use crate::component::commodity::Commodity;
+use crate::directive::{
+    commodity::Commodity as CommodityDirective, default_commodity::DefaultCommodity, Directive,
+};
+
+// display style of each commodity. declared by commodity and D directives, otherwise inferred
+// from the first posting amount using the commodity, with the largest precision seen.
+#[must_use]
+pub fn commodity_styles(directives: &[Directive]) -> HashMap<Commodity, CommodityStyle> {
+    let mut declared = HashMap::new();
+    let mut inferred = HashMap::<Commodity, CommodityStyle>::new();
+    let mut infer = |amount: &Amount| {
+        inferred
+            .entry(amount.commodity.clone())
+            .and_modify(|style| style.precision = style.precision.max(amount.style.precision))
+            .or_insert_with(|| amount.style.clone());
+    };
+    for directive in directives {
+        match directive {
+            Directive::Commodity(CommodityDirective::Amount(amount))
+            | Directive::DefaultCommodity(DefaultCommodity { amount }) => {
+                declared.insert(amount.commodity.clone(), amount.style.clone());
+            }
+            Directive::Transaction(transaction) => {
+                for posting in &transaction.postings {
+                    if let Some(amount) = &posting.amount {
+                        infer(amount);
+                    }
+                    if let Some(assertion) = &posting.assertion {
+                        infer(&assertion.amount);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    inferred.extend(declared);
+    inferred
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::component::amount::style::Side;
+
+    use super::*;
+
+    #[test]
+    fn declared_and_inferred() {
+        let directives = crate::parse(
+            "commodity 1.000,00 EUR
+2024-01-01
+    a  $1
+    b  $-1.505
+    c  3,00 EUR
+    d  10 gold
+    e  2.5 gold
+",
+        )
+        .unwrap();
+        let styles = commodity_styles(&directives);
+
+        let eur = &styles[&Commodity::from_str("EUR")];
+        assert_eq!(eur.digit_group_mark, Some('.'));
+        assert_eq!(eur.decimal_mark, Some(','));
+
+        let usd = &styles[&Commodity::from_str("$")];
+        assert_eq!(usd.side, Side::Left);
+        assert_eq!(usd.precision, 3);
+
+        let gold = &styles[&Commodity::from_str("gold")];
+        assert_eq!(gold.side, Side::Right);
+        assert!(gold.is_spaced);
+        assert_eq!(gold.decimal_mark, None);
+        assert_eq!(gold.precision, 1);
+    }
+}
@@ -0,0 +1,40 @@
+use std::borrow::Cow;
+
+// text of the journal in directives: copied into a String, or borrowed from the journal in a Cow
+// where it is used as written. the parsers are generic over it, so that both come from the same
+// grammar.
+pub trait Text<'a>: AsRef<str> + Clone + Default + PartialEq + 'a {
+    fn from_slice(slice: &'a str) -> Self;
+
+    fn from_string(string: String) -> Self;
+
+    fn into_string(self) -> String;
+}
+
+impl<'a> Text<'a> for String {
+    fn from_slice(slice: &'a str) -> Self {
+        slice.to_string()
+    }
+
+    fn from_string(string: String) -> Self {
+        string
+    }
+
+    fn into_string(self) -> String {
+        self
+    }
+}
+
+impl<'a> Text<'a> for Cow<'a, str> {
+    fn from_slice(slice: &'a str) -> Self {
+        Cow::Borrowed(slice)
+    }
+
+    fn from_string(string: String) -> Self {
+        Cow::Owned(string)
+    }
+
+    fn into_string(self) -> String {
+        self.into_owned()
+    }
+}
@@ -3,15 +3,16 @@ use chumsky::prelude::*;
 use crate::component::comment::{inline, Comment};
 use crate::component::whitespace::whitespace;
 use crate::state::State;
+use crate::text::Text;
 
-pub fn end_of_line<'a>(
-) -> impl Parser<'a, &'a str, Option<Comment>, extra::Full<Rich<'a, char>, State, ()>> {
+pub fn end_of_line<'a, T: Text<'a>>(
+) -> impl Parser<'a, &'a str, Option<Comment<T>>, extra::Full<Rich<'a, char>, State, ()>> {
     end_of_line_prefixed(0)
 }
 
-pub fn end_of_line_prefixed<'a>(
+pub fn end_of_line_prefixed<'a, T: Text<'a>>(
     prefix_whitespace: usize,
-) -> impl Parser<'a, &'a str, Option<Comment>, extra::Full<Rich<'a, char>, State, ()>> {
+) -> impl Parser<'a, &'a str, Option<Comment<T>>, extra::Full<Rich<'a, char>, State, ()>> {
     whitespace()
         .repeated()
         .at_least(prefix_whitespace)